@@ -0,0 +1,286 @@
+use std::io::Write;
+
+/// moves the cursor to the zero-based `column` and `row`.
+///
+/// # examples.
+///
+/// ```
+/// let mut output = vec![];
+///
+/// ari::console::move_to(&mut output, 0, 4).unwrap();
+/// ari::console::erase_line(&mut output).unwrap();
+///
+/// assert_eq!(output, b"\x1b[5;1H\x1b[2K");
+/// ```
+pub fn move_to(stream: &mut impl Write, column: u16, row: u16) -> Result<(), std::io::Error> {
+    write!(stream, "\x1b[{};{}H", row as u32 + 1, column as u32 + 1)
+}
+
+/// moves the cursor to the zero-based `column` of the current row.
+pub fn move_to_column(stream: &mut impl Write, column: u16) -> Result<(), std::io::Error> {
+    write!(stream, "\x1b[{}G", column as u32 + 1)
+}
+
+/// moves the cursor up by `count` rows, stopping at the top of the screen.
+pub fn move_up(stream: &mut impl Write, count: u16) -> Result<(), std::io::Error> {
+    move_by(stream, count, 'A')
+}
+
+/// moves the cursor down by `count` rows, stopping at the bottom of the screen.
+pub fn move_down(stream: &mut impl Write, count: u16) -> Result<(), std::io::Error> {
+    move_by(stream, count, 'B')
+}
+
+/// moves the cursor right by `count` columns, stopping at the edge of the screen.
+pub fn move_right(stream: &mut impl Write, count: u16) -> Result<(), std::io::Error> {
+    move_by(stream, count, 'C')
+}
+
+/// moves the cursor left by `count` columns, stopping at the edge of the screen.
+pub fn move_left(stream: &mut impl Write, count: u16) -> Result<(), std::io::Error> {
+    move_by(stream, count, 'D')
+}
+
+// a count of zero means one to most terminals, so nothing is written for it.
+fn move_by(stream: &mut impl Write, count: u16, direction: char) -> Result<(), std::io::Error> {
+    match count {
+        0 => Ok(()),
+        count => write!(stream, "\x1b[{}{}", count, direction),
+    }
+}
+
+/// saves the cursor position and attributes, to be restored by `restore_cursor`.
+pub fn save_cursor(stream: &mut impl Write) -> Result<(), std::io::Error> {
+    stream.write_all(b"\x1b7")
+}
+
+/// restores the cursor position and attributes saved by `save_cursor`.
+pub fn restore_cursor(stream: &mut impl Write) -> Result<(), std::io::Error> {
+    stream.write_all(b"\x1b8")
+}
+
+pub fn hide_cursor(stream: &mut impl Write) -> Result<(), std::io::Error> {
+    stream.write_all(b"\x1b[?25l")
+}
+
+pub fn show_cursor(stream: &mut impl Write) -> Result<(), std::io::Error> {
+    stream.write_all(b"\x1b[?25h")
+}
+
+/// erases the whole line the cursor is on, without moving the cursor.
+pub fn erase_line(stream: &mut impl Write) -> Result<(), std::io::Error> {
+    stream.write_all(b"\x1b[2K")
+}
+
+/// erases from the cursor to the end of its line.
+pub fn erase_to_end_of_line(stream: &mut impl Write) -> Result<(), std::io::Error> {
+    stream.write_all(b"\x1b[K")
+}
+
+/// erases from the cursor to the end of the screen.
+pub fn erase_to_end_of_screen(stream: &mut impl Write) -> Result<(), std::io::Error> {
+    stream.write_all(b"\x1b[J")
+}
+
+/// scrolls the contents of the scroll region up by `count` rows, adding blank rows at the bottom.
+pub fn scroll_up(stream: &mut impl Write, count: u16) -> Result<(), std::io::Error> {
+    move_by(stream, count, 'S')
+}
+
+/// scrolls the contents of the scroll region down by `count` rows, adding blank rows at the top.
+pub fn scroll_down(stream: &mut impl Write, count: u16) -> Result<(), std::io::Error> {
+    move_by(stream, count, 'T')
+}
+
+/// restricts scrolling to the zero-based rows `top` to `bottom`, inclusive. this also moves the cursor to the top left
+/// of the screen.
+pub fn set_scroll_region(
+    stream: &mut impl Write,
+    top: u16,
+    bottom: u16,
+) -> Result<(), std::io::Error> {
+    write!(stream, "\x1b[{};{}r", top as u32 + 1, bottom as u32 + 1)
+}
+
+/// lets the whole screen scroll again. see `set_scroll_region`.
+pub fn reset_scroll_region(stream: &mut impl Write) -> Result<(), std::io::Error> {
+    stream.write_all(b"\x1b[r")
+}
+
+/// switches to the alternate screen, which has no scrollback, saving the contents of the main screen.
+pub fn enter_alternate_screen(stream: &mut impl Write) -> Result<(), std::io::Error> {
+    stream.write_all(b"\x1b[?1049h")
+}
+
+/// switches back to the main screen, restoring its contents. see `enter_alternate_screen`.
+pub fn leave_alternate_screen(stream: &mut impl Write) -> Result<(), std::io::Error> {
+    stream.write_all(b"\x1b[?1049l")
+}
+
+macro_rules! screen_guard {
+    ($type:ident, $restore:expr) => {
+        impl<W> $type<W>
+        where
+            W: Write,
+        {
+            pub fn get_ref(&self) -> &W {
+                &self.stream
+            }
+
+            pub fn get_mut(&mut self) -> &mut W {
+                &mut self.stream
+            }
+        }
+
+        impl<W> Write for $type<W>
+        where
+            W: Write,
+        {
+            fn write(&mut self, buffer: &[u8]) -> Result<usize, std::io::Error> {
+                self.stream.write(buffer)
+            }
+
+            fn flush(&mut self) -> Result<(), std::io::Error> {
+                self.stream.flush()
+            }
+        }
+
+        impl<W> Drop for $type<W>
+        where
+            W: Write,
+        {
+            fn drop(&mut self) {
+                let _ = $restore(&mut self.stream);
+                let _ = self.stream.flush();
+            }
+        }
+    };
+}
+
+/// hides the cursor of a terminal `stream` until dropped, including when unwinding from a panic.
+///
+/// the guard writes through to `stream`, so that it may be drawn to while the cursor is hidden.
+///
+/// # examples.
+///
+/// ```
+/// # use ari::console::HiddenCursor;
+/// # use std::io::Write;
+///
+/// let mut output = vec![];
+///
+/// {
+///     let mut cursor = HiddenCursor::enable(&mut output).unwrap();
+///
+///     write!(cursor, "working").unwrap();
+/// }
+///
+/// assert_eq!(output, b"\x1b[?25lworking\x1b[?25h");
+///
+/// let output = std::sync::Mutex::new(vec![]);
+/// let result = std::panic::catch_unwind(|| {
+///     let mut output = output.lock().unwrap();
+///     let _cursor = HiddenCursor::enable(&mut *output).unwrap();
+///
+///     panic!("oops");
+/// });
+///
+/// assert!(result.is_err());
+/// assert_eq!(output.into_inner().unwrap_err().into_inner(), b"\x1b[?25l\x1b[?25h");
+/// ```
+pub struct HiddenCursor<W>
+where
+    W: Write,
+{
+    stream: W,
+}
+
+impl<W> HiddenCursor<W>
+where
+    W: Write,
+{
+    pub fn enable(mut stream: W) -> Result<HiddenCursor<W>, std::io::Error> {
+        hide_cursor(&mut stream)?;
+        stream.flush()?;
+
+        Ok(HiddenCursor { stream })
+    }
+}
+
+screen_guard!(HiddenCursor, show_cursor);
+
+/// switches a terminal `stream` to the alternate screen until dropped, including when unwinding from a panic. see
+/// `enter_alternate_screen`.
+///
+/// # examples.
+///
+/// ```
+/// # use ari::console::AlternateScreen;
+///
+/// let mut output = vec![];
+///
+/// {
+///     let mut screen = AlternateScreen::enable(&mut output).unwrap();
+///
+///     ari::console::clear_into(&mut screen).unwrap();
+/// }
+///
+/// assert_eq!(output, b"\x1b[?1049h\x1b[2J\x1b[1;1H\x1b[?1049l");
+/// ```
+pub struct AlternateScreen<W>
+where
+    W: Write,
+{
+    stream: W,
+}
+
+impl<W> AlternateScreen<W>
+where
+    W: Write,
+{
+    pub fn enable(mut stream: W) -> Result<AlternateScreen<W>, std::io::Error> {
+        enter_alternate_screen(&mut stream)?;
+        stream.flush()?;
+
+        Ok(AlternateScreen { stream })
+    }
+}
+
+screen_guard!(AlternateScreen, leave_alternate_screen);
+
+/// restricts scrolling of a terminal `stream` to the zero-based rows `top` to `bottom` until dropped, including when
+/// unwinding from a panic. see `set_scroll_region`.
+///
+/// # examples.
+///
+/// ```
+/// # use ari::console::ScrollRegion;
+///
+/// let mut output = vec![];
+///
+/// {
+///     let _region = ScrollRegion::enable(&mut output, 1, 20).unwrap();
+/// }
+///
+/// assert_eq!(output, b"\x1b[2;21r\x1b[r");
+/// ```
+pub struct ScrollRegion<W>
+where
+    W: Write,
+{
+    stream: W,
+}
+
+impl<W> ScrollRegion<W>
+where
+    W: Write,
+{
+    pub fn enable(mut stream: W, top: u16, bottom: u16) -> Result<ScrollRegion<W>, std::io::Error> {
+        set_scroll_region(&mut stream, top, bottom)?;
+        stream.flush()?;
+
+        Ok(ScrollRegion { stream })
+    }
+}
+
+screen_guard!(ScrollRegion, reset_scroll_region);
@@ -0,0 +1,719 @@
+use std::fs::OpenOptions;
+use std::io::{ErrorKind, IsTerminal, Write};
+use std::path::PathBuf;
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
+
+use crate::console::input::{
+    self, BracketedPaste, Event, EventReader, KeyCode, KeyEvent, Modifiers,
+};
+
+/// the default maximum number of history entries.
+const DEFAULT_HISTORY_LIMIT: usize = 1000;
+
+/// a tab completion callback. given the line and the byte offset of the cursor, it returns the byte offset where the
+/// text being completed starts, and the candidates to replace that text with.
+pub type Completer = Box<dyn FnMut(&str, usize) -> (usize, Vec<String>)>;
+
+/// an interactive line editor, with emacs style key bindings, history and tab completion.
+///
+/// the following keys are supported:
+///
+/// - `ctrl+a`, `home` and `ctrl+e`, `end`: move to the start or end of the line.
+/// - `ctrl+b`, `left` and `ctrl+f`, `right`: move by one character.
+/// - `alt+b`, `ctrl+left` and `alt+f`, `ctrl+right`: move by one word.
+/// - `backspace` and `ctrl+d`, `delete`: delete one character. `ctrl+d` on an empty line signals end of input.
+/// - `ctrl+w`, `alt+backspace` and `alt+d`: cut the previous or next word.
+/// - `ctrl+u` and `ctrl+k`: cut to the start or end of the line.
+/// - `ctrl+y`: paste the last cut text.
+/// - `ctrl+t`: swap the characters around the cursor.
+/// - `ctrl+p`, `up` and `ctrl+n`, `down`: move through history.
+/// - `ctrl+r`: search history incrementally. `ctrl+r` again finds older matches, `ctrl+g` or `escape` cancels.
+/// - `tab`: complete the text before the cursor. pressing `tab` twice lists the candidates.
+/// - `ctrl+l`: clear the screen.
+/// - `ctrl+c`: abandon the line.
+///
+/// the cursor moves over characters by their display width, so wide characters and combining marks are edited as one
+/// unit. a line wider than the terminal scrolls horizontally to keep the cursor in view.
+///
+/// # examples.
+///
+/// ```
+/// # use ari::console::input::{Event, KeyCode, KeyEvent, Modifiers};
+/// # use ari::console::LineEditor;
+///
+/// let mut editor = LineEditor::new();
+/// let key = |code, modifiers| Event::Key(KeyEvent::new(code, modifiers));
+/// let events = vec![
+///     key(KeyCode::Char('w'), Modifiers::NONE),
+///     key(KeyCode::Char('o'), Modifiers::NONE),
+///     key(KeyCode::Char('r'), Modifiers::NONE),
+///     key(KeyCode::Char('l'), Modifiers::NONE),
+///     key(KeyCode::Char('d'), Modifiers::NONE),
+///     key(KeyCode::Char('a'), Modifiers::CTRL),
+///     Event::Paste("hello, ".to_owned()),
+///     key(KeyCode::Enter, Modifiers::NONE),
+/// ];
+///
+/// let line = editor.read_line_with("> ", events, &mut vec![]).unwrap();
+///
+/// assert_eq!(line.as_deref(), Some("hello, world"));
+/// assert_eq!(editor.history(), ["hello, world"]);
+/// ```
+pub struct LineEditor {
+    history: Vec<String>,
+    history_file: Option<PathBuf>,
+    history_limit: usize,
+
+    // the number of lines in the history file, which is compacted once this exceeds the history limit.
+    history_file_lines: usize,
+    completer: Option<Completer>,
+    cut: String,
+}
+
+impl LineEditor {
+    pub fn new() -> LineEditor {
+        LineEditor {
+            history: vec![],
+            history_file: None,
+            history_limit: DEFAULT_HISTORY_LIMIT,
+            history_file_lines: 0,
+            completer: None,
+            cut: String::new(),
+        }
+    }
+
+    /// creates a line editor which loads history from `path`, if it exists, and appends every line read to it.
+    ///
+    /// only the most recent entries within the history limit are loaded. once the file holds more lines than the limit,
+    /// it is rewritten with the current history.
+    pub fn with_history_file(path: impl Into<PathBuf>) -> Result<LineEditor, std::io::Error> {
+        let path = path.into();
+        let mut editor = LineEditor::new();
+
+        match crate::fs::read_all_lines(&path) {
+            Ok(lines) => {
+                let lines = lines
+                    .into_iter()
+                    .filter(|x| !x.is_empty())
+                    .collect::<Vec<_>>();
+                let start = lines.len().saturating_sub(editor.history_limit);
+
+                editor.history_file_lines = lines.len();
+
+                for line in lines.into_iter().skip(start) {
+                    editor.add_history(line);
+                }
+            }
+            Err(error) if error.kind() == ErrorKind::NotFound => {}
+            Err(error) => return Err(error),
+        }
+
+        editor.history_file = Some(path);
+
+        Ok(editor)
+    }
+
+    /// sets the callback used for tab completion. see `Completer`.
+    ///
+    /// # examples.
+    ///
+    /// ```
+    /// # use ari::console::input::{Event, KeyCode, KeyEvent, Modifiers};
+    /// # use ari::console::LineEditor;
+    ///
+    /// let commands = ["status", "stash", "switch"];
+    /// let mut editor = LineEditor::new().on_complete(move |line, cursor| {
+    ///     let candidates = commands
+    ///         .iter()
+    ///         .filter(|x| x.starts_with(&line[..cursor]))
+    ///         .map(|x| x.to_string())
+    ///         .collect();
+    ///
+    ///     (0, candidates)
+    /// });
+    ///
+    /// let key = |code| Event::Key(KeyEvent::new(code, Modifiers::NONE));
+    /// let events = vec![
+    ///     key(KeyCode::Char('s')),
+    ///     key(KeyCode::Char('t')),
+    ///     key(KeyCode::Tab),
+    ///     key(KeyCode::Char('s')),
+    ///     key(KeyCode::Tab),
+    ///     key(KeyCode::Enter),
+    /// ];
+    ///
+    /// assert_eq!(editor.read_line_with("> ", events, &mut vec![]).unwrap().as_deref(), Some("stash"));
+    /// ```
+    pub fn on_complete(
+        mut self,
+        callback: impl FnMut(&str, usize) -> (usize, Vec<String>) + 'static,
+    ) -> LineEditor {
+        self.completer = Some(Box::new(callback));
+        self
+    }
+
+    /// sets the maximum number of history entries kept. the oldest entries are discarded first, and are removed from
+    /// the history file when the next line is accepted.
+    pub fn set_history_limit(&mut self, limit: usize) {
+        self.history_limit = limit;
+        self.trim_history();
+    }
+
+    /// returns the history, oldest first.
+    pub fn history(&self) -> &[String] {
+        &self.history
+    }
+
+    /// adds `line` to the history, unless it is empty or repeats the most recent entry.
+    pub fn add_history(&mut self, line: impl Into<String>) {
+        let line = line.into();
+
+        if !line.is_empty() && self.history.last() != Some(&line) {
+            self.history.push(line);
+            self.trim_history();
+        }
+    }
+
+    pub fn clear_history(&mut self) {
+        self.history.clear();
+    }
+
+    /// rewrites the history file with the current history. does nothing if there is no history file.
+    pub fn save_history(&self) -> Result<(), std::io::Error> {
+        match &self.history_file {
+            Some(path) => {
+                let mut text = self.history.join("\n");
+
+                text.push('\n');
+                crate::fs::write_all_text(path, text)
+            }
+            None => Ok(()),
+        }
+    }
+
+    /// reads a line from the terminal, displaying `prompt` before it.
+    ///
+    /// returns `None` at the end of input, or fails with `ErrorKind::Interrupted` if the user presses `ctrl+c`. when
+    /// stdin is not a terminal, a line is read without any editing.
+    pub fn read_line(&mut self, prompt: &str) -> Result<Option<String>, std::io::Error> {
+        if !std::io::stdin().is_terminal() {
+            let line = crate::io::stdin::prompt_line_with(
+                &mut std::io::stdin().lock(),
+                &mut std::io::stdout(),
+                prompt,
+            );
+
+            return match line {
+                Ok(line) => {
+                    self.accept(&line);
+                    Ok(Some(line))
+                }
+                Err(error) if error.kind() == ErrorKind::UnexpectedEof => Ok(None),
+                Err(error) => Err(error),
+            };
+        }
+
+        let _raw = input::raw_mode()?;
+        let _paste = BracketedPaste::enable(std::io::stdout())?;
+        let mut reader = EventReader::new()?;
+        let mut failure = None;
+        let events = std::iter::from_fn(|| match reader.read() {
+            Ok(event) => Some(event),
+            Err(error) => {
+                failure = Some(error);
+                None
+            }
+        });
+
+        let line = self.read_line_with(prompt, events, &mut std::io::stdout().lock());
+
+        match failure {
+            Some(error) if error.kind() != ErrorKind::UnexpectedEof => Err(error),
+            _ => line,
+        }
+    }
+
+    /// reads a line from `events`, rendering the prompt and line into `output`. output is written for a terminal in raw
+    /// mode. returns `None` if `events` ends before a line is accepted. see `read_line`.
+    pub fn read_line_with(
+        &mut self,
+        prompt: &str,
+        events: impl IntoIterator<Item = Event>,
+        output: &mut impl Write,
+    ) -> Result<Option<String>, std::io::Error> {
+        let mut events = events.into_iter();
+        let mut buffer = Buffer::default();
+        let mut position = self.history.len();
+        let mut draft = String::new();
+        let mut pending = None;
+        let mut tabbed = false;
+
+        buffer.render(output, prompt)?;
+
+        loop {
+            let key = match pending.take().map(Event::Key).or_else(|| events.next()) {
+                Some(Event::Key(key)) => key,
+                Some(Event::Paste(text)) => {
+                    buffer.insert(&text.replace(['\r', '\n'], " "));
+                    buffer.render(output, prompt)?;
+                    continue;
+                }
+                Some(Event::Resize) => {
+                    buffer.render(output, prompt)?;
+                    continue;
+                }
+                None => {
+                    output.write_all(b"\r\n")?;
+                    output.flush()?;
+
+                    return Ok(None);
+                }
+            };
+
+            let tab = key.code == KeyCode::Tab && key.modifiers.is_empty();
+
+            match (key.code, key.modifiers) {
+                (KeyCode::Enter, _) => {
+                    output.write_all(b"\r\n")?;
+                    output.flush()?;
+                    self.accept(&buffer.line);
+
+                    return Ok(Some(buffer.line));
+                }
+
+                (KeyCode::Char('c'), Modifiers::CTRL) => {
+                    output.write_all(b"^C\r\n")?;
+                    output.flush()?;
+
+                    return Err(ErrorKind::Interrupted.into());
+                }
+
+                (KeyCode::Char('d'), Modifiers::CTRL) if buffer.line.is_empty() => {
+                    output.write_all(b"\r\n")?;
+                    output.flush()?;
+
+                    return Ok(None);
+                }
+
+                (KeyCode::Char('a'), Modifiers::CTRL) | (KeyCode::Home, _) => buffer.cursor = 0,
+                (KeyCode::Char('e'), Modifiers::CTRL) | (KeyCode::End, _) => {
+                    buffer.cursor = buffer.line.len()
+                }
+                (KeyCode::Char('b'), Modifiers::CTRL) | (KeyCode::Left, Modifiers::NONE) => {
+                    buffer.cursor = buffer.previous(buffer.cursor)
+                }
+                (KeyCode::Char('f'), Modifiers::CTRL) | (KeyCode::Right, Modifiers::NONE) => {
+                    buffer.cursor = buffer.next(buffer.cursor)
+                }
+                (KeyCode::Char('b'), Modifiers::ALT) | (KeyCode::Left, _) => {
+                    buffer.cursor = buffer.previous_word(buffer.cursor, char::is_alphanumeric)
+                }
+                (KeyCode::Char('f'), Modifiers::ALT) | (KeyCode::Right, _) => {
+                    buffer.cursor = buffer.next_word(buffer.cursor)
+                }
+
+                (KeyCode::Backspace, x) if !x.alt => {
+                    let start = buffer.previous(buffer.cursor);
+
+                    buffer.remove(start, buffer.cursor);
+                }
+                (KeyCode::Char('d'), Modifiers::CTRL) | (KeyCode::Delete, _) => {
+                    let end = buffer.next(buffer.cursor);
+
+                    buffer.remove(buffer.cursor, end);
+                }
+                (KeyCode::Char('w'), Modifiers::CTRL) => {
+                    let start = buffer.previous_word(buffer.cursor, |x| !x.is_whitespace());
+
+                    self.cut = buffer.remove(start, buffer.cursor);
+                }
+                (KeyCode::Backspace, _) => {
+                    let start = buffer.previous_word(buffer.cursor, char::is_alphanumeric);
+
+                    self.cut = buffer.remove(start, buffer.cursor);
+                }
+                (KeyCode::Char('d'), Modifiers::ALT) => {
+                    let end = buffer.next_word(buffer.cursor);
+
+                    self.cut = buffer.remove(buffer.cursor, end);
+                }
+                (KeyCode::Char('u'), Modifiers::CTRL) => self.cut = buffer.remove(0, buffer.cursor),
+                (KeyCode::Char('k'), Modifiers::CTRL) => {
+                    self.cut = buffer.remove(buffer.cursor, buffer.line.len())
+                }
+                (KeyCode::Char('y'), Modifiers::CTRL) => buffer.insert(&self.cut),
+                (KeyCode::Char('t'), Modifiers::CTRL) => buffer.transpose(),
+
+                (KeyCode::Char('l'), Modifiers::CTRL) => crate::console::clear_into(output)?,
+
+                (KeyCode::Char('p'), Modifiers::CTRL) | (KeyCode::Up, _) if position > 0 => {
+                    if position == self.history.len() {
+                        draft = buffer.line.clone();
+                    }
+
+                    position -= 1;
+                    buffer.set(&self.history[position]);
+                }
+                (KeyCode::Char('n'), Modifiers::CTRL) | (KeyCode::Down, _)
+                    if position < self.history.len() =>
+                {
+                    position += 1;
+
+                    match self.history.get(position) {
+                        Some(line) => buffer.set(line),
+                        None => buffer.set(&draft),
+                    }
+                }
+
+                (KeyCode::Char('r'), Modifiers::CTRL) => {
+                    let original = buffer.line.clone();
+
+                    match self.search(&mut events, output)? {
+                        Search::Accepted(index, offset, key) => {
+                            position = index;
+                            buffer.set(&self.history[index]);
+                            buffer.cursor = offset;
+                            pending = Some(key);
+                        }
+                        Search::Cancelled => buffer.set(&original),
+                        Search::Ended => {}
+                    }
+                }
+
+                (KeyCode::Tab, Modifiers::NONE) => self.complete(&mut buffer, tabbed, output)?,
+
+                (KeyCode::Char(x), modifiers) if !modifiers.ctrl && !modifiers.alt => {
+                    buffer.insert(x.encode_utf8(&mut [0; 4]))
+                }
+
+                _ => {}
+            }
+
+            tabbed = tab;
+            buffer.render(output, prompt)?;
+        }
+    }
+
+    // adds an accepted line to the history, and appends it to the history file, compacting the file if it has grown
+    // past the history limit.
+    fn accept(&mut self, line: &str) {
+        if line.is_empty() || self.history.last().map(|x| x.as_str()) == Some(line) {
+            return;
+        }
+
+        self.add_history(line);
+
+        // a history file which cannot be written must not prevent input from being read.
+        if let Some(path) = &self.history_file {
+            if self.history_file_lines >= self.history_limit {
+                if self.save_history().is_ok() {
+                    self.history_file_lines = self.history.len();
+                }
+            } else if let Ok(mut file) = OpenOptions::new().create(true).append(true).open(path) {
+                if writeln!(file, "{}", line).is_ok() {
+                    self.history_file_lines += 1;
+                }
+            }
+        }
+    }
+
+    fn trim_history(&mut self) {
+        if self.history.len() > self.history_limit {
+            let excess = self.history.len() - self.history_limit;
+
+            self.history.drain(..excess);
+        }
+    }
+
+    // runs an incremental reverse search through history until a key ends it.
+    fn search(
+        &mut self,
+        events: &mut impl Iterator<Item = Event>,
+        output: &mut impl Write,
+    ) -> Result<Search, std::io::Error> {
+        let mut query = String::new();
+        let mut found: Option<(usize, usize)> = None;
+        let mut failing = false;
+
+        loop {
+            let entry = found
+                .map(|(index, _)| self.history[index].as_str())
+                .unwrap_or("");
+            let label = if failing {
+                "failing reverse-i-search"
+            } else {
+                "reverse-i-search"
+            };
+
+            let text = format!("({})`{}': {}", label, query, entry);
+            let text = match crate::console::size() {
+                Some(size) => {
+                    crate::str::truncate_ansi(&text, (size.columns as usize).saturating_sub(1), "")
+                }
+                None => text,
+            };
+
+            write!(output, "\r{}\x1b[K", text)?;
+            output.flush()?;
+
+            // searches for `query` in entries older than `before`.
+            let find = |query: &str, before: usize| {
+                self.history[..before]
+                    .iter()
+                    .enumerate()
+                    .rev()
+                    .find_map(|(i, x)| x.find(query).map(|offset| (i, offset)))
+            };
+
+            let newest = self.history.len();
+            let current = found.map(|x| x.0 + 1).unwrap_or(newest);
+            let key = match events.next() {
+                Some(Event::Key(key)) => key,
+                Some(Event::Paste(text)) => {
+                    query.push_str(&text);
+
+                    let result = find(&query, current);
+
+                    failing = result.is_none();
+                    found = result.or(found);
+                    continue;
+                }
+                Some(Event::Resize) => continue,
+                None => return Ok(Search::Ended),
+            };
+
+            let result = match (key.code, key.modifiers) {
+                (KeyCode::Char('r'), Modifiers::CTRL) => {
+                    find(&query, found.map(|x| x.0).unwrap_or(newest))
+                }
+                (KeyCode::Char('g'), Modifiers::CTRL) | (KeyCode::Escape, _) => {
+                    return Ok(Search::Cancelled)
+                }
+                (KeyCode::Backspace, _) => {
+                    query.pop();
+                    find(&query, newest)
+                }
+                (KeyCode::Char(x), modifiers) if !modifiers.ctrl && !modifiers.alt => {
+                    query.push(x);
+                    find(&query, current)
+                }
+                _ => {
+                    return Ok(match found {
+                        Some((index, offset)) => Search::Accepted(index, offset, key),
+                        None => Search::Cancelled,
+                    })
+                }
+            };
+
+            failing = result.is_none();
+            found = result.or(found);
+        }
+    }
+
+    // completes the text before the cursor, listing the candidates if completion is ambiguous and tab was pressed
+    // twice.
+    fn complete(
+        &mut self,
+        buffer: &mut Buffer,
+        tabbed: bool,
+        output: &mut impl Write,
+    ) -> Result<(), std::io::Error> {
+        let completer = match self.completer.as_mut() {
+            Some(completer) => completer,
+            None => return Ok(()),
+        };
+
+        let (start, candidates) = completer(&buffer.line, buffer.cursor);
+        let start = start.min(buffer.cursor);
+
+        if candidates.is_empty() || !buffer.line.is_char_boundary(start) {
+            return Ok(());
+        }
+
+        let prefix = candidates[1..]
+            .iter()
+            .fold(candidates[0].as_str(), |prefix, x| common_prefix(prefix, x));
+
+        if prefix.len() > buffer.cursor - start {
+            buffer.remove(start, buffer.cursor);
+            buffer.insert(prefix);
+        } else if candidates.len() > 1 && tabbed {
+            output.write_all(b"\r\n")?;
+            output.write_all(candidates.join("  ").as_bytes())?;
+            output.write_all(b"\r\n")?;
+        }
+
+        Ok(())
+    }
+}
+
+impl Default for LineEditor {
+    fn default() -> LineEditor {
+        LineEditor::new()
+    }
+}
+
+enum Search {
+    /// a history entry was chosen, with the cursor at `offset`. the key which ended the search should be handled.
+    Accepted(usize, usize, KeyEvent),
+    Cancelled,
+    Ended,
+}
+
+// a line being edited. `cursor` is a byte offset into `line`, always on a character boundary.
+#[derive(Default)]
+struct Buffer {
+    line: String,
+    cursor: usize,
+
+    // the offset of the first character shown, when the line is scrolled horizontally to fit the terminal.
+    scroll: usize,
+}
+
+impl Buffer {
+    // draws the prompt and line over the current row. a line wider than the terminal is scrolled horizontally to keep
+    // the cursor visible, since a line which wrapped onto further rows could not be redrawn from its start.
+    fn render(&mut self, output: &mut impl Write, prompt: &str) -> Result<(), std::io::Error> {
+        // the last column is left free, so that a cursor at the end of the line does not wrap.
+        let available = crate::console::size().map(|x| {
+            (x.columns as usize)
+                .saturating_sub(crate::str::display_width(prompt) + 1)
+                .max(1)
+        });
+
+        if self.scroll > self.cursor {
+            self.scroll = self.cursor;
+        }
+
+        if !self.line.is_char_boundary(self.scroll) {
+            self.scroll = 0;
+        }
+
+        match available {
+            Some(available) => {
+                while UnicodeWidthStr::width(&self.line[self.scroll..self.cursor]) > available {
+                    self.scroll = self.next(self.scroll);
+                }
+
+                // scroll back once text is deleted, so that no space is left unused.
+                while self.scroll > 0
+                    && UnicodeWidthStr::width(&self.line[self.previous(self.scroll)..]) <= available
+                {
+                    self.scroll = self.previous(self.scroll);
+                }
+            }
+            None => self.scroll = 0,
+        }
+
+        let mut visible = &self.line[self.scroll..];
+
+        if let Some(available) = available {
+            let mut width = 0;
+            let end = visible
+                .char_indices()
+                .find(|(_, x)| {
+                    width += x.width().unwrap_or(0);
+                    width > available
+                })
+                .map(|(i, _)| i)
+                .unwrap_or(visible.len());
+
+            visible = &visible[..end];
+        }
+
+        write!(output, "\r{}{}\x1b[K", prompt, visible)?;
+
+        let cursor = (self.cursor - self.scroll).min(visible.len());
+        let after = UnicodeWidthStr::width(&visible[cursor..]);
+
+        if after > 0 {
+            write!(output, "\x1b[{}D", after)?;
+        }
+
+        output.flush()
+    }
+
+    fn set(&mut self, line: &str) {
+        self.line = line.to_owned();
+        self.cursor = self.line.len();
+        self.scroll = 0;
+    }
+
+    fn insert(&mut self, text: &str) {
+        self.line.insert_str(self.cursor, text);
+        self.cursor += text.len();
+    }
+
+    // removes and returns the text between two offsets, leaving the cursor at `start`.
+    fn remove(&mut self, start: usize, end: usize) -> String {
+        self.cursor = start;
+        self.line.drain(start..end).collect()
+    }
+
+    // swaps the characters either side of the cursor, or the last two characters at the end of the line.
+    fn transpose(&mut self) {
+        let middle = match self.cursor == self.line.len() {
+            true => self.previous(self.cursor),
+            false => self.cursor,
+        };
+
+        let start = self.previous(middle);
+        let end = self.next(middle);
+
+        if start < middle && middle < end {
+            let swapped = format!("{}{}", &self.line[middle..end], &self.line[start..middle]);
+
+            self.line.replace_range(start..end, &swapped);
+            self.cursor = end;
+        }
+    }
+
+    // returns the offset of the character before `offset`, treating zero width characters as part of the one they
+    // follow.
+    fn previous(&self, offset: usize) -> usize {
+        self.line[..offset]
+            .char_indices()
+            .rev()
+            .find(|(_, x)| x.width().unwrap_or(0) > 0)
+            .map(|(i, _)| i)
+            .unwrap_or(0)
+    }
+
+    // returns the offset of the character after `offset`, skipping any zero width characters which follow it.
+    fn next(&self, offset: usize) -> usize {
+        self.line[offset..]
+            .char_indices()
+            .skip(1)
+            .find(|(_, x)| x.width().unwrap_or(0) > 0)
+            .map(|(i, _)| offset + i)
+            .unwrap_or(self.line.len())
+    }
+
+    // returns the start of the word before `offset`, where words are runs of characters matching `word`.
+    fn previous_word(&self, offset: usize, word: impl Fn(char) -> bool) -> usize {
+        let text = &self.line[..offset];
+        let end = text.trim_end_matches(|x| !word(x)).len();
+
+        text[..end].trim_end_matches(word).len()
+    }
+
+    // returns the end of the word after `offset`, where words are runs of alphanumeric characters.
+    fn next_word(&self, offset: usize) -> usize {
+        let text = &self.line[offset..];
+        let rest = text.trim_start_matches(|x: char| !x.is_alphanumeric());
+        let rest = rest.trim_start_matches(char::is_alphanumeric);
+
+        self.line.len() - rest.len()
+    }
+}
+
+fn common_prefix<'a>(a: &'a str, b: &str) -> &'a str {
+    let length = a
+        .char_indices()
+        .zip(b.chars())
+        .find(|((_, x), y)| x != y)
+        .map(|((i, _), _)| i)
+        .unwrap_or_else(|| a.len().min(b.len()));
+
+    &a[..length]
+}
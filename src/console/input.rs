@@ -0,0 +1,542 @@
+// raw terminal input. keys are decoded from the escape sequences sent by xterm compatible terminals, which includes
+// the windows console once virtual terminal input is enabled.
+
+use std::io::{ErrorKind, Write};
+use std::time::{Duration, Instant};
+
+use crate::console::sys;
+
+/// how long to wait for the rest of an escape sequence before treating a lone escape as the escape key.
+const ESCAPE_TIMEOUT: Duration = Duration::from_millis(50);
+
+/// the sequence which terminates bracketed paste content.
+const PASTE_END: &[u8] = b"\x1b[201~";
+
+/// modifier keys held while a key was pressed.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Modifiers {
+    pub shift: bool,
+    pub alt: bool,
+    pub ctrl: bool,
+}
+
+impl Modifiers {
+    pub const NONE: Modifiers = Modifiers {
+        shift: false,
+        alt: false,
+        ctrl: false,
+    };
+
+    pub const SHIFT: Modifiers = Modifiers {
+        shift: true,
+        alt: false,
+        ctrl: false,
+    };
+
+    pub const ALT: Modifiers = Modifiers {
+        shift: false,
+        alt: true,
+        ctrl: false,
+    };
+
+    pub const CTRL: Modifiers = Modifiers {
+        shift: false,
+        alt: false,
+        ctrl: true,
+    };
+
+    pub fn is_empty(&self) -> bool {
+        !(self.shift || self.alt || self.ctrl)
+    }
+
+    // decodes an xterm modifier parameter, which is one plus a bitmask of shift, alt and ctrl.
+    fn from_xterm(parameter: u32) -> Modifiers {
+        let bits = parameter.saturating_sub(1);
+
+        Modifiers {
+            shift: bits & 1 != 0,
+            alt: bits & 2 != 0,
+            ctrl: bits & 4 != 0,
+        }
+    }
+}
+
+/// a key on the keyboard.
+///
+/// control characters are reported as `Char` with `Modifiers::CTRL`, so `ctrl+c` is `Char('c')`. shifted characters
+/// are reported as the character they produce, without `Modifiers::SHIFT`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum KeyCode {
+    Char(char),
+    Enter,
+    Tab,
+    Backspace,
+    Escape,
+    Up,
+    Down,
+    Left,
+    Right,
+    Home,
+    End,
+    PageUp,
+    PageDown,
+    Insert,
+    Delete,
+
+    /// a function key, from `F(1)` through `F(12)`.
+    F(u8),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct KeyEvent {
+    pub code: KeyCode,
+    pub modifiers: Modifiers,
+}
+
+impl KeyEvent {
+    pub fn new(code: KeyCode, modifiers: Modifiers) -> KeyEvent {
+        KeyEvent { code, modifiers }
+    }
+
+    fn with(self, modifiers: Modifiers) -> KeyEvent {
+        KeyEvent::new(
+            self.code,
+            Modifiers {
+                shift: self.modifiers.shift || modifiers.shift,
+                alt: self.modifiers.alt || modifiers.alt,
+                ctrl: self.modifiers.ctrl || modifiers.ctrl,
+            },
+        )
+    }
+}
+
+impl From<KeyCode> for KeyEvent {
+    fn from(code: KeyCode) -> KeyEvent {
+        KeyEvent::new(code, Modifiers::NONE)
+    }
+}
+
+/// an input event read from the terminal.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Event {
+    Key(KeyEvent),
+
+    /// text pasted while bracketed paste is enabled. see `BracketedPaste`.
+    Paste(String),
+
+    /// the terminal was resized. only reported on unix.
+    Resize,
+}
+
+/// switches the terminal into raw mode until dropped, including when unwinding from a panic.
+///
+/// in raw mode, input is delivered byte by byte without being echoed, and keys such as `ctrl+c` are delivered as input
+/// instead of generating signals. output post-processing is also disabled, so lines must end with `\r\n`.
+pub struct RawMode {
+    _guard: sys::RawModeGuard,
+}
+
+/// switches the terminal into raw mode. fails if stdin is not a terminal.
+pub fn raw_mode() -> Result<RawMode, std::io::Error> {
+    Ok(RawMode {
+        _guard: sys::enable_raw_mode()?,
+    })
+}
+
+/// enables bracketed paste on a terminal `stream` until dropped, so that pasted text is reported as a single
+/// `Event::Paste` rather than as individual keys.
+///
+/// # examples.
+///
+/// ```
+/// # use ari::console::input::BracketedPaste;
+///
+/// let mut output = vec![];
+///
+/// {
+///     let _paste = BracketedPaste::enable(&mut output).unwrap();
+/// }
+///
+/// assert_eq!(output, b"\x1b[?2004h\x1b[?2004l");
+/// ```
+pub struct BracketedPaste<W>
+where
+    W: Write,
+{
+    stream: W,
+}
+
+impl<W> BracketedPaste<W>
+where
+    W: Write,
+{
+    pub fn enable(mut stream: W) -> Result<BracketedPaste<W>, std::io::Error> {
+        stream.write_all(b"\x1b[?2004h")?;
+        stream.flush()?;
+
+        Ok(BracketedPaste { stream })
+    }
+}
+
+impl<W> Drop for BracketedPaste<W>
+where
+    W: Write,
+{
+    fn drop(&mut self) {
+        let _ = self.stream.write_all(b"\x1b[?2004l");
+        let _ = self.stream.flush();
+    }
+}
+
+/// decodes terminal input bytes into events.
+///
+/// input may be fed in arbitrary chunks. incomplete escape sequences and characters are buffered until the rest
+/// arrives, or until `flush` is called.
+///
+/// # examples.
+///
+/// ```
+/// # use ari::console::input::{Event, EventDecoder, KeyCode, KeyEvent, Modifiers};
+///
+/// let mut decoder = EventDecoder::new();
+///
+/// decoder.feed(b"a\x1b[1;5A\x03\x1b[200~hi\x1b[201~\x1b");
+///
+/// assert_eq!(decoder.next_event(), Some(Event::Key(KeyCode::Char('a').into())));
+/// assert_eq!(decoder.next_event(), Some(Event::Key(KeyEvent::new(KeyCode::Up, Modifiers::CTRL))));
+/// assert_eq!(decoder.next_event(), Some(Event::Key(KeyEvent::new(KeyCode::Char('c'), Modifiers::CTRL))));
+/// assert_eq!(decoder.next_event(), Some(Event::Paste("hi".to_owned())));
+///
+/// // a lone escape may be the start of a sequence, so it is only decoded once flushed.
+/// assert_eq!(decoder.next_event(), None);
+/// assert_eq!(decoder.flush(), Some(Event::Key(KeyCode::Escape.into())));
+///
+/// // a paste may arrive in several chunks, and is only decoded once it ends.
+/// decoder.feed(b"\x1b[200~one\r");
+///
+/// assert_eq!(decoder.next_event(), None);
+/// assert!(decoder.is_pasting());
+///
+/// decoder.feed(b"two\x1b[201~");
+///
+/// assert_eq!(decoder.next_event(), Some(Event::Paste("one\rtwo".to_owned())));
+/// assert!(!decoder.is_pasting());
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct EventDecoder {
+    buffer: Vec<u8>,
+}
+
+impl EventDecoder {
+    pub fn new() -> EventDecoder {
+        EventDecoder { buffer: vec![] }
+    }
+
+    /// appends input bytes to be decoded.
+    pub fn feed(&mut self, data: &[u8]) {
+        self.buffer.extend_from_slice(data);
+    }
+
+    /// returns true if there is undecoded input, such as an incomplete escape sequence.
+    pub fn has_pending(&self) -> bool {
+        !self.buffer.is_empty()
+    }
+
+    /// returns true if a bracketed paste has started but has not yet ended. its content may arrive slowly, so it should
+    /// not be flushed after a timeout.
+    pub fn is_pasting(&self) -> bool {
+        self.buffer.starts_with(b"\x1b[200~")
+    }
+
+    /// decodes the next complete event, or returns `None` if more input is needed.
+    pub fn next_event(&mut self) -> Option<Event> {
+        self.decode(false)
+    }
+
+    /// decodes the next event, assuming that no more input will arrive to complete it. a lone escape is decoded as
+    /// the escape key.
+    pub fn flush(&mut self) -> Option<Event> {
+        self.decode(true)
+    }
+
+    fn decode(&mut self, complete: bool) -> Option<Event> {
+        while !self.buffer.is_empty() {
+            let (event, length) = decode(&self.buffer, complete)?;
+
+            self.buffer.drain(..length);
+
+            if event.is_some() {
+                return event;
+            }
+        }
+
+        None
+    }
+}
+
+/// reads events from the terminal. stdin should be in raw mode, see `raw_mode`.
+///
+/// # examples.
+///
+/// ```no_run
+/// # use ari::console::input::{self, Event, EventReader, KeyCode};
+/// # use std::time::Duration;
+///
+/// let _raw = input::raw_mode()?;
+/// let mut reader = EventReader::new()?;
+///
+/// loop {
+///     match reader.read_timeout(Duration::from_secs(1))? {
+///         Some(Event::Key(key)) if key.code == KeyCode::Char('q') => break,
+///         Some(event) => print!("{:?}\r\n", event),
+///         None => print!("tick\r\n"),
+///     }
+/// }
+/// # Ok::<(), std::io::Error>(())
+/// ```
+pub struct EventReader {
+    decoder: EventDecoder,
+    resize: sys::ResizeSubscription,
+}
+
+impl EventReader {
+    pub fn new() -> Result<EventReader, std::io::Error> {
+        Ok(EventReader {
+            decoder: EventDecoder::new(),
+            resize: sys::ResizeSubscription::new()?,
+        })
+    }
+
+    /// blocks until an event is available.
+    ///
+    /// fails with `ErrorKind::UnexpectedEof` once stdin is closed and all input has been decoded.
+    pub fn read(&mut self) -> Result<Event, std::io::Error> {
+        loop {
+            if let Some(event) = self.read_until(None)? {
+                return Ok(event);
+            }
+        }
+    }
+
+    /// blocks until an event is available or `timeout` passes, returning `None` on timeout.
+    pub fn read_timeout(&mut self, timeout: Duration) -> Result<Option<Event>, std::io::Error> {
+        self.read_until(Some(Instant::now() + timeout))
+    }
+
+    fn read_until(&mut self, deadline: Option<Instant>) -> Result<Option<Event>, std::io::Error> {
+        loop {
+            if let Some(event) = self.decoder.next_event() {
+                return Ok(Some(event));
+            }
+
+            // only an incomplete escape sequence is flushed after a timeout, never an unfinished paste.
+            let pending = self.decoder.has_pending() && !self.decoder.is_pasting();
+            let timeout = deadline.map(|x| x.saturating_duration_since(Instant::now()));
+            let timeout = match pending {
+                true => Some(timeout.map_or(ESCAPE_TIMEOUT, |x| x.min(ESCAPE_TIMEOUT))),
+                false => timeout,
+            };
+
+            let ready = sys::wait_input(timeout, Some(&self.resize))?;
+
+            if ready.resized {
+                return Ok(Some(Event::Resize));
+            }
+
+            if ready.input {
+                let mut buffer = [0u8; 1024];
+                let read = sys::read_input(&mut buffer)?;
+
+                if read == 0 {
+                    return match self.decoder.flush() {
+                        Some(event) => Ok(Some(event)),
+                        None => Err(ErrorKind::UnexpectedEof.into()),
+                    };
+                }
+
+                self.decoder.feed(&buffer[..read]);
+                continue;
+            }
+
+            if pending {
+                if let Some(event) = self.decoder.flush() {
+                    return Ok(Some(event));
+                }
+            }
+
+            if deadline.map(|x| Instant::now() >= x).unwrap_or(false) {
+                return Ok(None);
+            }
+        }
+    }
+}
+
+// decodes one event from the start of `bytes`, returning it and the number of bytes consumed. bytes which do not
+// decode to anything are consumed with no event. returns `None` if more input is needed, which never happens when
+// `complete` is set.
+fn decode(bytes: &[u8], complete: bool) -> Option<(Option<Event>, usize)> {
+    match bytes.first()? {
+        0x1b => decode_escape(bytes, complete),
+        _ => decode_key(bytes, complete).map(|(key, length)| (key.map(Event::Key), length)),
+    }
+}
+
+fn decode_escape(bytes: &[u8], complete: bool) -> Option<(Option<Event>, usize)> {
+    let key = |code: KeyCode, modifiers: Modifiers, length: usize| {
+        Some((Some(Event::Key(KeyEvent::new(code, modifiers))), length))
+    };
+
+    match bytes.get(1) {
+        None if complete => key(KeyCode::Escape, Modifiers::NONE, 1),
+        None => None,
+        Some(b'[') => decode_csi(bytes, complete),
+        Some(b'O') => match bytes.get(2) {
+            None if complete => key(KeyCode::Char('O'), Modifiers::ALT, 2),
+            None => None,
+            Some(x) => match final_key(*x) {
+                Some(code) => key(code, Modifiers::NONE, 3),
+                None => Some((None, 3)),
+            },
+        },
+        Some(_) => {
+            let (key, length) = decode_key(&bytes[1..], complete)?;
+
+            Some((key.map(|x| Event::Key(x.with(Modifiers::ALT))), length + 1))
+        }
+    }
+}
+
+// decodes a control sequence introducer: `ESC [`, parameter bytes, intermediate bytes, then a final byte.
+fn decode_csi(bytes: &[u8], complete: bool) -> Option<(Option<Event>, usize)> {
+    let end = match bytes[2..].iter().position(|x| !(0x20..0x40).contains(x)) {
+        Some(x) => x + 2,
+        None if complete && bytes.len() == 2 => {
+            let key = KeyEvent::new(KeyCode::Char('['), Modifiers::ALT);
+
+            return Some((Some(Event::Key(key)), 2));
+        }
+        None if complete => return Some((None, bytes.len())),
+        None => return None,
+    };
+
+    let length = end + 1;
+    let parameters = std::str::from_utf8(&bytes[2..end])
+        .unwrap_or_default()
+        .split(';')
+        .map(|x| x.parse::<u32>().ok())
+        .collect::<Vec<_>>();
+    let first = parameters.first().copied().flatten();
+    let modifiers = Modifiers::from_xterm(parameters.get(1).copied().flatten().unwrap_or(1));
+    let key = |code: KeyCode| Some((Some(Event::Key(KeyEvent::new(code, modifiers))), length));
+
+    match bytes[end] {
+        b'Z' => {
+            let key = KeyEvent::new(KeyCode::Tab, Modifiers::SHIFT);
+
+            Some((Some(Event::Key(key)), length))
+        }
+
+        b'~' if first == Some(200) => match find(&bytes[length..], PASTE_END) {
+            Some(x) => {
+                let text = String::from_utf8_lossy(&bytes[length..length + x]).into_owned();
+
+                Some((Some(Event::Paste(text)), length + x + PASTE_END.len()))
+            }
+            None if complete => {
+                let text = String::from_utf8_lossy(&bytes[length..]).into_owned();
+
+                Some((Some(Event::Paste(text)), bytes.len()))
+            }
+            None => None,
+        },
+
+        b'~' => match first.and_then(tilde_key) {
+            Some(code) => key(code),
+            None => Some((None, length)),
+        },
+
+        x => match final_key(x) {
+            Some(code) => key(code),
+            None => Some((None, length)),
+        },
+    }
+}
+
+// decodes a single key which is not an escape sequence. returns `None` if `bytes` holds an incomplete utf-8 character.
+fn decode_key(bytes: &[u8], complete: bool) -> Option<(Option<KeyEvent>, usize)> {
+    let control = |x: u8| {
+        Some((
+            Some(KeyEvent::new(KeyCode::Char(x as char), Modifiers::CTRL)),
+            1,
+        ))
+    };
+    let key = |code: KeyCode| Some((Some(KeyEvent::from(code)), 1));
+
+    let length = match bytes[0] {
+        b'\r' | b'\n' => return key(KeyCode::Enter),
+        b'\t' => return key(KeyCode::Tab),
+        0x7f | 0x08 => return key(KeyCode::Backspace),
+        0x1b => return key(KeyCode::Escape),
+        0x00 => return control(b' '),
+        x @ 0x01..=0x1a => return control(x + 0x60),
+        x @ 0x1c..=0x1f => return control(x + 0x40),
+        0x20..=0x7e => 1,
+        0xc0..=0xdf => 2,
+        0xe0..=0xef => 3,
+        0xf0..=0xf7 => 4,
+        _ => return Some((None, 1)),
+    };
+
+    if bytes.len() < length {
+        return match complete {
+            true => Some((None, bytes.len())),
+            false => None,
+        };
+    }
+
+    match std::str::from_utf8(&bytes[..length]) {
+        Ok(text) => {
+            let character = text.chars().next()?;
+
+            Some((Some(KeyEvent::from(KeyCode::Char(character))), length))
+        }
+        Err(_) => Some((None, 1)),
+    }
+}
+
+// returns the key for the final byte of a `CSI` or `SS3` sequence.
+fn final_key(byte: u8) -> Option<KeyCode> {
+    match byte {
+        b'A' => Some(KeyCode::Up),
+        b'B' => Some(KeyCode::Down),
+        b'C' => Some(KeyCode::Right),
+        b'D' => Some(KeyCode::Left),
+        b'H' => Some(KeyCode::Home),
+        b'F' => Some(KeyCode::End),
+        b'P' => Some(KeyCode::F(1)),
+        b'Q' => Some(KeyCode::F(2)),
+        b'R' => Some(KeyCode::F(3)),
+        b'S' => Some(KeyCode::F(4)),
+        _ => None,
+    }
+}
+
+// returns the key for the first parameter of a `CSI ... ~` sequence.
+fn tilde_key(parameter: u32) -> Option<KeyCode> {
+    match parameter {
+        1 | 7 => Some(KeyCode::Home),
+        2 => Some(KeyCode::Insert),
+        3 => Some(KeyCode::Delete),
+        4 | 8 => Some(KeyCode::End),
+        5 => Some(KeyCode::PageUp),
+        6 => Some(KeyCode::PageDown),
+        11..=15 => Some(KeyCode::F((parameter - 10) as u8)),
+        17..=21 => Some(KeyCode::F((parameter - 11) as u8)),
+        23 | 24 => Some(KeyCode::F((parameter - 12) as u8)),
+        _ => None,
+    }
+}
+
+fn find(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    haystack.windows(needle.len()).position(|x| x == needle)
+}
@@ -1,3 +1,20 @@
+pub mod input;
+
+mod control;
+mod editor;
+mod progress;
+mod size;
+mod style;
+mod sys;
+mod table;
+
+pub use self::control::*;
+pub use self::editor::*;
+pub use self::progress::*;
+pub use self::size::*;
+pub use self::style::*;
+pub use self::table::*;
+
 use std::io::Write;
 
 /// clears `stdout`.
@@ -0,0 +1,575 @@
+use parking_lot::Mutex;
+use std::fmt::Write as _;
+use std::io::{IsTerminal, Write};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use crate::fmt::{HumanBytes, HumanDuration};
+use crate::str::PadString;
+use crate::time::Stopwatch;
+
+/// the minimum duration between two redraws on a terminal.
+const TERMINAL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// the minimum duration between two log lines when not drawing to a terminal.
+const LOG_INTERVAL: Duration = Duration::from_secs(5);
+
+/// the width of `{bar}` when none is given.
+const DEFAULT_BAR_WIDTH: usize = 40;
+
+const DEFAULT_TEMPLATE: &str = "{msg} [{bar}] {pos}/{len} ({percent}%) eta {eta}";
+
+const DEFAULT_SPINNER_TEMPLATE: &str = "{spinner} {msg} {pos} ({elapsed})";
+
+const SPINNER: &[char] = &['⠋', '⠙', '⠹', '⠸', '⠼', '⠴', '⠦', '⠧', '⠇', '⠏'];
+
+/// the duration each spinner frame is displayed for.
+const SPINNER_FRAME: Duration = Duration::from_millis(80);
+
+/// a progress bar, or a spinner when the length is unknown.
+///
+/// a progress bar may be cloned and updated from several threads. redraws are throttled, and when stderr is not a
+/// terminal, progress is instead written as a plain log line every few seconds.
+///
+/// the line is rendered from a template, in which the following placeholders are replaced:
+///
+/// - `{bar}`: the bar itself. a width may be given, as in `{bar:20}`.
+/// - `{spinner}`: a spinner, which animates while the bar is redrawn.
+/// - `{pos}`, `{len}` and `{percent}`: the position, length and percentage complete.
+/// - `{bytes}`, `{total_bytes}` and `{bytes_per_sec}`: the position, length and rate as a number of bytes.
+/// - `{per_sec}`: the rate, as a number of items per second.
+/// - `{elapsed}` and `{eta}`: the time elapsed since the bar was created, and the estimated time remaining.
+/// - `{msg}`: the message. see `set_message`.
+///
+/// other placeholders may also be given a width, to which they are padded.
+///
+/// # examples.
+///
+/// ```
+/// # use ari::console::ProgressBar;
+///
+/// let bar = ProgressBar::hidden(200);
+///
+/// bar.set_template("{msg:6}[{bar:10}] {pos}/{len} {percent}%");
+/// bar.set_message("copy");
+/// bar.inc(50);
+///
+/// assert_eq!(bar.render(), "copy  [==>       ] 50/200 25%");
+///
+/// bar.finish();
+///
+/// assert_eq!(bar.render(), "copy  [==========] 200/200 100%");
+/// ```
+#[derive(Clone)]
+pub struct ProgressBar {
+    state: Arc<Mutex<BarState>>,
+
+    // the target this bar draws to, shared by every clone so that `MultiProgress::add` moves all of them.
+    target: Arc<Mutex<Arc<Mutex<Target>>>>,
+}
+
+impl ProgressBar {
+    /// creates a progress bar of `length` items, which draws to stderr.
+    pub fn new(length: u64) -> ProgressBar {
+        ProgressBar::create(Some(length), Target::stderr())
+    }
+
+    /// creates a spinner, for progress of unknown length, which draws to stderr.
+    pub fn spinner() -> ProgressBar {
+        ProgressBar::create(None, Target::stderr())
+    }
+
+    /// creates a progress bar of `length` items, which is never drawn.
+    pub fn hidden(length: u64) -> ProgressBar {
+        ProgressBar::create(Some(length), Target::hidden())
+    }
+
+    fn create(length: Option<u64>, target: Target) -> ProgressBar {
+        let state = Arc::new(Mutex::new(BarState {
+            position: 0,
+            length,
+            message: String::new(),
+            template: None,
+            stopwatch: Stopwatch::started(),
+            finished: false,
+            logged: false,
+        }));
+
+        let mut target = target;
+
+        target.bars.push(state.clone());
+
+        ProgressBar {
+            state,
+            target: Arc::new(Mutex::new(Arc::new(Mutex::new(target)))),
+        }
+    }
+
+    pub fn position(&self) -> u64 {
+        self.state.lock().position
+    }
+
+    pub fn length(&self) -> Option<u64> {
+        self.state.lock().length
+    }
+
+    pub fn elapsed(&self) -> Duration {
+        self.state.lock().stopwatch.elapsed()
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.state.lock().finished
+    }
+
+    /// advances the position by `delta`.
+    pub fn inc(&self, delta: u64) {
+        self.update(|x| x.position = x.position.saturating_add(delta));
+    }
+
+    pub fn set_position(&self, position: u64) {
+        self.update(|x| x.position = position);
+    }
+
+    /// sets the length, or `None` to display a spinner.
+    pub fn set_length(&self, length: Option<u64>) {
+        self.update(|x| x.length = length);
+    }
+
+    pub fn set_message(&self, message: impl Into<String>) {
+        let message = message.into();
+
+        self.update(|x| x.message = message);
+    }
+
+    /// sets the template used to render this bar. see `ProgressBar`.
+    pub fn set_template(&self, template: impl Into<String>) {
+        let template = template.into();
+
+        self.update(|x| x.template = Some(template));
+    }
+
+    /// sets the minimum duration between two redraws. defaults to 100 milliseconds on a terminal, and five seconds
+    /// otherwise.
+    pub fn set_draw_interval(&self, interval: Duration) {
+        self.target().lock().interval = interval;
+    }
+
+    /// redraws this bar if it is due, animating a spinner even if progress has not changed.
+    pub fn tick(&self) {
+        self.target().lock().draw(false);
+    }
+
+    /// completes this bar, moving it to its full length and drawing it one last time.
+    pub fn finish(&self) {
+        self.state.lock().finish();
+        self.target().lock().draw(true);
+    }
+
+    pub fn finish_with_message(&self, message: impl Into<String>) {
+        let message = message.into();
+
+        self.state.lock().message = message;
+        self.finish();
+    }
+
+    /// completes this bar and removes it from the terminal.
+    pub fn finish_and_clear(&self) {
+        let target = self.target();
+        let mut target = target.lock();
+        let mut state = self.state.lock();
+
+        state.finish();
+        state.logged = true;
+        drop(state);
+
+        target.bars.retain(|x| !Arc::ptr_eq(x, &self.state));
+        target.draw(true);
+    }
+
+    /// prints `line` above the progress bars, without disturbing them.
+    pub fn println(&self, line: impl AsRef<str>) {
+        self.target().lock().println(line.as_ref());
+    }
+
+    /// renders this bar, without drawing it.
+    pub fn render(&self) -> String {
+        self.state.lock().render()
+    }
+
+    fn update(&self, change: impl FnOnce(&mut BarState)) {
+        change(&mut self.state.lock());
+        self.target().lock().draw(false);
+    }
+
+    fn target(&self) -> Arc<Mutex<Target>> {
+        self.target.lock().clone()
+    }
+}
+
+/// several progress bars, stacked and redrawn in place together.
+///
+/// # examples.
+///
+/// ```no_run
+/// # use ari::console::{MultiProgress, ProgressBar};
+///
+/// let progress = MultiProgress::new();
+/// let workers = (0..4)
+///     .map(|i| {
+///         let bar = progress.add(ProgressBar::new(100));
+///
+///         bar.set_message(format!("worker {}", i));
+///
+///         std::thread::spawn(move || {
+///             for _ in 0..100 {
+///                 bar.inc(1);
+///                 std::thread::sleep(std::time::Duration::from_millis(10));
+///             }
+///
+///             bar.finish();
+///         })
+///     })
+///     .collect::<Vec<_>>();
+///
+/// for worker in workers {
+///     worker.join().unwrap();
+/// }
+/// ```
+#[derive(Clone)]
+pub struct MultiProgress {
+    target: Arc<Mutex<Target>>,
+}
+
+impl MultiProgress {
+    /// creates a set of progress bars which draws to stderr.
+    pub fn new() -> MultiProgress {
+        MultiProgress {
+            target: Arc::new(Mutex::new(Target::stderr())),
+        }
+    }
+
+    /// moves `bar` into this set, below any existing bars, returning it. clones of `bar` are moved too.
+    pub fn add(&self, bar: ProgressBar) -> ProgressBar {
+        let mut slot = bar.target.lock();
+
+        {
+            let mut previous = slot.lock();
+
+            previous.bars.retain(|x| !Arc::ptr_eq(x, &bar.state));
+            previous.draw(true);
+        }
+
+        let mut target = self.target.lock();
+
+        target.bars.push(bar.state.clone());
+        target.draw(true);
+        drop(target);
+
+        *slot = self.target.clone();
+        drop(slot);
+
+        bar
+    }
+
+    /// sets the minimum duration between two redraws. see `ProgressBar::set_draw_interval`.
+    pub fn set_draw_interval(&self, interval: Duration) {
+        self.target.lock().interval = interval;
+    }
+
+    /// prints `line` above the progress bars, without disturbing them.
+    pub fn println(&self, line: impl AsRef<str>) {
+        self.target.lock().println(line.as_ref());
+    }
+
+    /// removes every bar from the terminal.
+    pub fn clear(&self) {
+        let mut target = self.target.lock();
+
+        target.clear();
+        target.bars.clear();
+    }
+}
+
+impl Default for MultiProgress {
+    fn default() -> MultiProgress {
+        MultiProgress::new()
+    }
+}
+
+struct BarState {
+    position: u64,
+    length: Option<u64>,
+    message: String,
+    template: Option<String>,
+    stopwatch: Stopwatch,
+    finished: bool,
+
+    // true once the final state of a finished bar has been logged, when not drawing to a terminal.
+    logged: bool,
+}
+
+impl BarState {
+    fn finish(&mut self) {
+        if let Some(length) = self.length {
+            self.position = length;
+        }
+
+        self.finished = true;
+    }
+
+    fn render(&self) -> String {
+        let template = match (&self.template, self.length) {
+            (Some(template), _) => template.as_str(),
+            (None, Some(_)) => DEFAULT_TEMPLATE,
+            (None, None) => DEFAULT_SPINNER_TEMPLATE,
+        };
+
+        let mut line = String::new();
+        let mut rest = template;
+
+        while let Some(start) = rest.find('{') {
+            line.push_str(&rest[..start]);
+            rest = &rest[start..];
+
+            let end = match rest.find('}') {
+                Some(end) => end,
+                None => break,
+            };
+
+            let (key, width) = match rest[1..end].split_once(':') {
+                Some((key, width)) => (key, width.parse::<usize>().ok()),
+                None => (&rest[1..end], None),
+            };
+
+            match self.placeholder(key, width) {
+                Some(value) => line.push_str(&value),
+                None => line.push_str(&rest[..=end]),
+            }
+
+            rest = &rest[end + 1..];
+        }
+
+        line.push_str(rest);
+        line
+    }
+
+    fn placeholder(&self, key: &str, width: Option<usize>) -> Option<String> {
+        let elapsed = self.stopwatch.elapsed();
+        let seconds = elapsed.as_secs_f64();
+        let rate = match seconds > 0.0 {
+            true => self.position as f64 / seconds,
+            false => 0.0,
+        };
+
+        let fraction = match self.length {
+            Some(0) => 1.0,
+            Some(length) => (self.position as f64 / length as f64).min(1.0),
+            None => 0.0,
+        };
+
+        let length =
+            |x: &dyn Fn(u64) -> String| self.length.map(x).unwrap_or_else(|| "?".to_owned());
+        let value = match key {
+            "bar" => return Some(self.bar(fraction, width.unwrap_or(DEFAULT_BAR_WIDTH))),
+            "spinner" => {
+                let frame = (elapsed.as_millis() / SPINNER_FRAME.as_millis()) as usize;
+
+                match self.finished {
+                    true => " ".to_owned(),
+                    false => SPINNER[frame % SPINNER.len()].to_string(),
+                }
+            }
+            "pos" => self.position.to_string(),
+            "len" => length(&|x| x.to_string()),
+            "percent" => format!("{:.0}", fraction * 100.0),
+            "bytes" => HumanBytes(self.position).to_string(),
+            "total_bytes" => length(&|x| HumanBytes(x).to_string()),
+            "bytes_per_sec" => format!("{}/s", HumanBytes(rate as u64)),
+            "per_sec" => format!("{:.1}/s", rate),
+            "elapsed" => format!("{:#}", HumanDuration(elapsed)),
+            "eta" => match self.length {
+                Some(length) if self.position >= length => {
+                    format!("{:#}", HumanDuration(Duration::default()))
+                }
+                Some(length) if rate > 0.0 => {
+                    let remaining = (length - self.position) as f64 / rate;
+
+                    format!("{:#}", HumanDuration(Duration::from_secs_f64(remaining)))
+                }
+                _ => "?".to_owned(),
+            },
+            "msg" => self.message.clone(),
+            _ => return None,
+        };
+
+        match width {
+            Some(width) => Some(value.pad_right(width)),
+            None => Some(value),
+        }
+    }
+
+    fn bar(&self, fraction: f64, width: usize) -> String {
+        if self.length.is_none() {
+            return " ".repeat(width);
+        }
+
+        let filled = ((fraction * width as f64) as usize).min(width);
+        let mut bar = "=".repeat(filled);
+
+        if filled < width {
+            bar.push(if filled > 0 { '>' } else { ' ' });
+            bar.push_str(&" ".repeat(width - filled - 1));
+        }
+
+        bar
+    }
+}
+
+// where, and how, a set of bars is drawn.
+struct Target {
+    output: Option<Box<dyn Write + Send>>,
+    terminal: bool,
+    interval: Duration,
+    drawn: Option<Instant>,
+
+    // the number of lines currently drawn on the terminal. the cursor is left at the end of the last line.
+    lines: usize,
+    bars: Vec<Arc<Mutex<BarState>>>,
+}
+
+impl Target {
+    fn stderr() -> Target {
+        let terminal = std::io::stderr().is_terminal();
+
+        Target {
+            output: Some(Box::new(std::io::stderr())),
+            terminal,
+            interval: if terminal {
+                TERMINAL_INTERVAL
+            } else {
+                LOG_INTERVAL
+            },
+            drawn: None,
+            lines: 0,
+            bars: vec![],
+        }
+    }
+
+    fn hidden() -> Target {
+        Target {
+            output: None,
+            terminal: false,
+            interval: LOG_INTERVAL,
+            drawn: None,
+            lines: 0,
+            bars: vec![],
+        }
+    }
+
+    // draws every bar, unless the last draw was too recent and `force` is not set.
+    fn draw(&mut self, force: bool) {
+        let now = Instant::now();
+
+        if self.output.is_none()
+            || (!force && self.drawn.map(|x| now - x < self.interval).unwrap_or(false))
+        {
+            return;
+        }
+
+        self.drawn = Some(now);
+
+        let mut text = String::new();
+
+        if self.terminal {
+            self.rewind(&mut text);
+
+            // lines wider than the terminal would wrap onto rows which `rewind` does not know about. each line is erased
+            // before it is written, since erasing after a line of the full width would remove its last character.
+            let columns = crate::console::size().map(|x| x.columns as usize);
+            let lines = self
+                .bars
+                .iter()
+                .map(|x| x.lock().render())
+                .map(|x| match columns {
+                    Some(columns) => crate::str::truncate_ansi(&x, columns, ""),
+                    None => x,
+                })
+                .collect::<Vec<_>>();
+
+            for (i, line) in lines.iter().enumerate() {
+                let separator = if i > 0 { "\n" } else { "" };
+
+                let _ = write!(text, "{}\r\x1b[K{}", separator, line);
+            }
+
+            self.lines = lines.len();
+
+            // once every bar is finished, leave them on screen and move past them.
+            if self.bars.iter().all(|x| x.lock().finished) {
+                if self.lines > 0 {
+                    text.push('\n');
+                }
+
+                self.lines = 0;
+                self.bars.clear();
+            }
+        } else {
+            for bar in &self.bars {
+                let mut bar = bar.lock();
+
+                if !bar.logged {
+                    text.push_str(&bar.render());
+                    text.push('\n');
+                    bar.logged = bar.finished;
+                }
+            }
+
+            self.bars.retain(|x| !x.lock().finished);
+        }
+
+        self.write(&text);
+    }
+
+    fn println(&mut self, line: &str) {
+        let mut text = String::new();
+
+        if self.terminal {
+            self.rewind(&mut text);
+            self.lines = 0;
+        }
+
+        text.push_str(line);
+        text.push('\n');
+        self.write(&text);
+        self.draw(true);
+    }
+
+    fn clear(&mut self) {
+        let mut text = String::new();
+
+        if self.terminal {
+            self.rewind(&mut text);
+            self.lines = 0;
+        }
+
+        self.write(&text);
+    }
+
+    // moves the cursor to the start of the first drawn line, and erases everything below it.
+    fn rewind(&self, text: &mut String) {
+        if self.lines > 1 {
+            let _ = write!(text, "\x1b[{}A", self.lines - 1);
+        }
+
+        if self.lines > 0 {
+            text.push_str("\r\x1b[J");
+        }
+    }
+
+    fn write(&mut self, text: &str) {
+        if let Some(output) = self.output.as_mut() {
+            let _ = output.write_all(text.as_bytes());
+            let _ = output.flush();
+        }
+    }
+}
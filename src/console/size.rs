@@ -0,0 +1,157 @@
+use std::sync::Arc;
+use std::thread::JoinHandle;
+use std::time::Duration;
+
+use crate::console::sys;
+use crate::sync::{AtomicArBool, ManualResetEvent, ResetEvent};
+
+/// the interval at which a `ResizeWatcher` checks the terminal size, for platforms without a resize signal.
+const POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+/// the size of a terminal, in character cells.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct TerminalSize {
+    pub columns: u16,
+    pub rows: u16,
+}
+
+/// returns the size of the terminal attached to stdout, stderr or stdin.
+///
+/// if none of them is a terminal, falls back to the `COLUMNS` and `LINES` environment variables, and otherwise returns
+/// `None`.
+pub fn size() -> Option<TerminalSize> {
+    sys::window_size()
+        .map(|(columns, rows)| TerminalSize { columns, rows })
+        .or_else(size_from_environment)
+}
+
+fn size_from_environment() -> Option<TerminalSize> {
+    let variable = |name| {
+        std::env::var(name)
+            .ok()
+            .and_then(|x| x.trim().parse::<u16>().ok())
+            .filter(|x| *x > 0)
+    };
+
+    Some(TerminalSize {
+        columns: variable("COLUMNS")?,
+        rows: variable("LINES")?,
+    })
+}
+
+/// watches the terminal for resizes on a background thread, until dropped.
+///
+/// on unix, resizes are delivered by `SIGWINCH`. elsewhere, the terminal size is polled a few times a second.
+///
+/// # examples.
+///
+/// ```no_run
+/// # use ari::console::ResizeWatcher;
+/// # use ari::sync::ResetEvent;
+///
+/// let watcher = ResizeWatcher::new().unwrap();
+///
+/// loop {
+///     watcher.event().wait();
+///     watcher.event().reset();
+///
+///     println!("resized to {:?}", watcher.size());
+/// }
+/// ```
+pub struct ResizeWatcher {
+    shared: Arc<Shared>,
+    thread: Option<JoinHandle<()>>,
+}
+
+struct Shared {
+    event: ManualResetEvent,
+    size: parking_lot::Mutex<Option<TerminalSize>>,
+    subscription: sys::ResizeSubscription,
+    stopped: AtomicArBool,
+}
+
+impl ResizeWatcher {
+    /// starts watching the terminal. the event returned by `event` is signalled on every resize.
+    pub fn new() -> Result<ResizeWatcher, std::io::Error> {
+        ResizeWatcher::start(|_| {})
+    }
+
+    fn start(
+        mut callback: impl FnMut(Option<TerminalSize>) + Send + 'static,
+    ) -> Result<ResizeWatcher, std::io::Error> {
+        let shared = Arc::new(Shared {
+            event: ManualResetEvent::new(false),
+            size: parking_lot::Mutex::new(size()),
+            subscription: sys::ResizeSubscription::new()?,
+            stopped: AtomicArBool::new(false),
+        });
+
+        let thread = {
+            let shared = shared.clone();
+
+            std::thread::Builder::new()
+                .name("ari-resize-watcher".to_owned())
+                .spawn(move || loop {
+                    let signalled = shared
+                        .subscription
+                        .wait(Some(POLL_INTERVAL))
+                        .unwrap_or(false);
+
+                    if shared.stopped.get() {
+                        break;
+                    }
+
+                    let current = size();
+                    let changed = std::mem::replace(&mut *shared.size.lock(), current) != current;
+
+                    if signalled || changed {
+                        shared.event.set();
+                        callback(current);
+                    }
+                })?
+        };
+
+        Ok(ResizeWatcher {
+            shared,
+            thread: Some(thread),
+        })
+    }
+
+    /// the event signalled whenever the terminal is resized. it stays signalled until reset.
+    pub fn event(&self) -> &ManualResetEvent {
+        &self.shared.event
+    }
+
+    /// returns the terminal size, as of the most recent resize.
+    pub fn size(&self) -> Option<TerminalSize> {
+        *self.shared.size.lock()
+    }
+}
+
+impl Drop for ResizeWatcher {
+    fn drop(&mut self) {
+        self.shared.stopped.set(true);
+        self.shared.subscription.wake();
+
+        if let Some(thread) = self.thread.take() {
+            thread.join().ok();
+        }
+    }
+}
+
+/// calls `callback` with the new terminal size whenever the terminal is resized, until the returned watcher is
+/// dropped. the callback runs on a background thread.
+///
+/// # examples.
+///
+/// ```no_run
+/// let watcher = ari::console::on_resize(|size| eprintln!("resized to {:?}", size)).unwrap();
+///
+/// std::thread::sleep(std::time::Duration::from_secs(10));
+/// drop(watcher);
+/// ```
+pub fn on_resize(
+    callback: impl FnMut(Option<TerminalSize>) + Send + 'static,
+) -> Result<ResizeWatcher, std::io::Error> {
+    ResizeWatcher::start(callback)
+}
@@ -0,0 +1,515 @@
+use std::fmt::{Display, Formatter, Write};
+use std::io::IsTerminal;
+use std::sync::OnceLock;
+
+/// the xterm default values of the 16 basic colors, used to downgrade colors for terminals with limited support.
+const PALETTE: [(u8, u8, u8); 16] = [
+    (0, 0, 0),
+    (205, 0, 0),
+    (0, 205, 0),
+    (205, 205, 0),
+    (0, 0, 238),
+    (205, 0, 205),
+    (0, 205, 205),
+    (229, 229, 229),
+    (127, 127, 127),
+    (255, 0, 0),
+    (0, 255, 0),
+    (255, 255, 0),
+    (92, 92, 255),
+    (255, 0, 255),
+    (0, 255, 255),
+    (255, 255, 255),
+];
+
+/// a terminal color.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Color {
+    Black,
+    Red,
+    Green,
+    Yellow,
+    Blue,
+    Magenta,
+    Cyan,
+    White,
+    BrightBlack,
+    BrightRed,
+    BrightGreen,
+    BrightYellow,
+    BrightBlue,
+    BrightMagenta,
+    BrightCyan,
+    BrightWhite,
+
+    /// a color from the 256 color palette.
+    Ansi256(u8),
+
+    /// a 24-bit color.
+    Rgb(u8, u8, u8),
+}
+
+impl Color {
+    /// returns the closest color that can be displayed with `support`, or `None` if colors are not supported.
+    ///
+    /// # examples.
+    ///
+    /// ```
+    /// # use ari::console::{Color, ColorSupport};
+    ///
+    /// assert_eq!(Color::Rgb(255, 0, 0).downgrade(ColorSupport::Ansi256), Some(Color::Ansi256(196)));
+    /// assert_eq!(Color::Rgb(250, 10, 5).downgrade(ColorSupport::Basic), Some(Color::BrightRed));
+    /// assert_eq!(Color::Red.downgrade(ColorSupport::None), None);
+    /// ```
+    pub fn downgrade(self, support: ColorSupport) -> Option<Color> {
+        match (self, support) {
+            (_, ColorSupport::None) => None,
+            (Color::Rgb(r, g, b), ColorSupport::Ansi256) => {
+                Some(Color::Ansi256(rgb_to_ansi256(r, g, b)))
+            }
+            (Color::Rgb(r, g, b), ColorSupport::Basic) => Some(nearest_basic(r, g, b)),
+            (Color::Ansi256(x), ColorSupport::Basic) if x >= 16 => {
+                let (r, g, b) = ansi256_to_rgb(x);
+
+                Some(nearest_basic(r, g, b))
+            }
+            (Color::Ansi256(x), ColorSupport::Basic) => Some(BASIC[x as usize]),
+            (color, _) => Some(color),
+        }
+    }
+
+    // writes the sgr parameters which select this color, either as a foreground or a background.
+    fn write(self, formatter: &mut Formatter, background: bool) -> Result<(), std::fmt::Error> {
+        let offset = if background { 10 } else { 0 };
+        let extended = if background { 48 } else { 38 };
+
+        match self {
+            Color::Ansi256(x) => write!(formatter, "{};5;{}", extended, x),
+            Color::Rgb(r, g, b) => write!(formatter, "{};2;{};{};{}", extended, r, g, b),
+            color => {
+                let index = BASIC.iter().position(|x| *x == color).unwrap_or(0) as u8;
+                let code = match index < 8 {
+                    true => 30 + index,
+                    false => 90 + index - 8,
+                };
+
+                write!(formatter, "{}", code + offset)
+            }
+        }
+    }
+}
+
+const BASIC: [Color; 16] = [
+    Color::Black,
+    Color::Red,
+    Color::Green,
+    Color::Yellow,
+    Color::Blue,
+    Color::Magenta,
+    Color::Cyan,
+    Color::White,
+    Color::BrightBlack,
+    Color::BrightRed,
+    Color::BrightGreen,
+    Color::BrightYellow,
+    Color::BrightBlue,
+    Color::BrightMagenta,
+    Color::BrightCyan,
+    Color::BrightWhite,
+];
+
+/// the styling capabilities of a terminal.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum ColorSupport {
+    /// no styling at all.
+    None,
+
+    /// the 16 basic colors, and text attributes.
+    Basic,
+
+    /// the 256 color palette.
+    Ansi256,
+
+    /// 24-bit colors.
+    TrueColor,
+}
+
+impl ColorSupport {
+    /// returns the support of stdout, detected once and then cached. see `detect`.
+    pub fn stdout() -> ColorSupport {
+        static SUPPORT: OnceLock<ColorSupport> = OnceLock::new();
+
+        *SUPPORT.get_or_init(|| ColorSupport::detect(std::io::stdout().is_terminal()))
+    }
+
+    /// returns the support of stderr, detected once and then cached. see `detect`.
+    pub fn stderr() -> ColorSupport {
+        static SUPPORT: OnceLock<ColorSupport> = OnceLock::new();
+
+        *SUPPORT.get_or_init(|| ColorSupport::detect(std::io::stderr().is_terminal()))
+    }
+
+    /// detects the support of a stream from the environment, given whether the stream is a terminal.
+    ///
+    /// - `NO_COLOR` disables styling.
+    /// - `CLICOLOR_FORCE` enables styling even if the stream is not a terminal, and `CLICOLOR=0` disables it otherwise.
+    /// - `COLORTERM=truecolor` or `24bit` enables 24-bit colors, and a `TERM` containing `256color` enables the 256
+    ///   color palette. `TERM=dumb` disables styling.
+    ///
+    /// on windows, the console supports 24-bit colors once virtual terminal processing is enabled by `ari::initialize`.
+    pub fn detect(is_terminal: bool) -> ColorSupport {
+        ColorSupport::detect_with(is_terminal, |name| std::env::var(name).ok())
+    }
+
+    /// detects support like `detect`, looking up variables with `lookup` instead of the process environment.
+    ///
+    /// # examples.
+    ///
+    /// ```
+    /// # use ari::console::ColorSupport;
+    ///
+    /// let environment = |pairs: &'static [(&'static str, &'static str)]| {
+    ///     move |name: &str| pairs.iter().find(|x| x.0 == name).map(|x| x.1.to_owned())
+    /// };
+    ///
+    /// assert_eq!(ColorSupport::detect_with(true, environment(&[("TERM", "xterm-256color")])), ColorSupport::Ansi256);
+    /// assert_eq!(ColorSupport::detect_with(false, environment(&[("TERM", "xterm-256color")])), ColorSupport::None);
+    /// assert_eq!(ColorSupport::detect_with(false, environment(&[("CLICOLOR_FORCE", "1")])), ColorSupport::Basic);
+    /// assert_eq!(ColorSupport::detect_with(true, environment(&[("COLORTERM", "truecolor"), ("NO_COLOR", "1")])), ColorSupport::None);
+    /// ```
+    pub fn detect_with(is_terminal: bool, lookup: impl Fn(&str) -> Option<String>) -> ColorSupport {
+        let set = |name: &str| lookup(name).filter(|x| !x.is_empty());
+        let term = set("TERM");
+        let forced = set("CLICOLOR_FORCE").map(|x| x != "0").unwrap_or(false);
+
+        if set("NO_COLOR").is_some() {
+            return ColorSupport::None;
+        }
+
+        if !forced && (!is_terminal || set("CLICOLOR").as_deref() == Some("0")) {
+            return ColorSupport::None;
+        }
+
+        if let Some(x) = set("COLORTERM") {
+            if x == "truecolor" || x == "24bit" {
+                return ColorSupport::TrueColor;
+            }
+        }
+
+        match term.as_deref() {
+            Some("dumb") if forced => ColorSupport::Basic,
+            Some("dumb") => ColorSupport::None,
+            Some(x) if x.contains("256color") => ColorSupport::Ansi256,
+            Some(x) if x.ends_with("-direct") => ColorSupport::TrueColor,
+            None if cfg!(windows) => ColorSupport::TrueColor,
+            _ => ColorSupport::Basic,
+        }
+    }
+}
+
+/// text styling: colors and attributes.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Style {
+    pub foreground: Option<Color>,
+    pub background: Option<Color>,
+    pub bold: bool,
+    pub dim: bool,
+    pub italic: bool,
+    pub underline: bool,
+}
+
+macro_rules! style_methods {
+    ($type: ty) => {
+        /// sets the foreground color.
+        pub fn fg(mut self, color: Color) -> $type {
+            self.style_mut().foreground = Some(color);
+            self
+        }
+
+        /// sets the background color.
+        pub fn bg(mut self, color: Color) -> $type {
+            self.style_mut().background = Some(color);
+            self
+        }
+
+        pub fn bold(mut self) -> $type {
+            self.style_mut().bold = true;
+            self
+        }
+
+        pub fn dim(mut self) -> $type {
+            self.style_mut().dim = true;
+            self
+        }
+
+        pub fn italic(mut self) -> $type {
+            self.style_mut().italic = true;
+            self
+        }
+
+        pub fn underline(mut self) -> $type {
+            self.style_mut().underline = true;
+            self
+        }
+    };
+}
+
+impl Style {
+    pub fn new() -> Style {
+        Style::default()
+    }
+
+    style_methods!(Style);
+
+    fn style_mut(&mut self) -> &mut Style {
+        self
+    }
+
+    /// applies this style to `value`.
+    pub fn apply<T>(self, value: T) -> Styled<T> {
+        Styled {
+            value,
+            style: self,
+            support: None,
+        }
+    }
+
+    // writes the escape sequence which enables this style, downgrading colors to `support`. writes nothing if there is
+    // nothing to enable.
+    fn write_prefix(
+        &self,
+        formatter: &mut Formatter,
+        support: ColorSupport,
+    ) -> Result<bool, std::fmt::Error> {
+        let mut first = true;
+        let mut separator = |formatter: &mut Formatter| {
+            let text = if first { "\x1b[" } else { ";" };
+
+            first = false;
+            formatter.write_str(text)
+        };
+
+        for (enabled, code) in &[
+            (self.bold, "1"),
+            (self.dim, "2"),
+            (self.italic, "3"),
+            (self.underline, "4"),
+        ] {
+            if *enabled {
+                separator(formatter)?;
+                formatter.write_str(code)?;
+            }
+        }
+
+        if let Some(color) = self.foreground.and_then(|x| x.downgrade(support)) {
+            separator(formatter)?;
+            color.write(formatter, false)?;
+        }
+
+        if let Some(color) = self.background.and_then(|x| x.downgrade(support)) {
+            separator(formatter)?;
+            color.write(formatter, true)?;
+        }
+
+        if !first {
+            formatter.write_str("m")?;
+        }
+
+        Ok(!first)
+    }
+}
+
+/// a value displayed with a style. colors are downgraded to what the terminal supports, and no escape sequences are
+/// written at all if styling is not supported.
+///
+/// # examples.
+///
+/// ```
+/// # use ari::console::{self, Color, ColorSupport};
+///
+/// let text = console::style("ready").fg(Color::Green).bold().with_support(ColorSupport::Basic);
+///
+/// assert_eq!(text.to_string(), "\x1b[1;32mready\x1b[0m");
+/// assert_eq!(text.with_support(ColorSupport::None).to_string(), "ready");
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Styled<T> {
+    value: T,
+    style: Style,
+    support: Option<ColorSupport>,
+}
+
+/// styles `value` for display on stdout. see `Styled`.
+pub fn style<T>(value: T) -> Styled<T> {
+    Style::new().apply(value)
+}
+
+impl<T> Styled<T> {
+    style_methods!(Styled<T>);
+
+    fn style_mut(&mut self) -> &mut Style {
+        &mut self.style
+    }
+
+    /// displays this value for a terminal with `support`, instead of the support of stdout.
+    pub fn with_support(mut self, support: ColorSupport) -> Styled<T> {
+        self.support = Some(support);
+        self
+    }
+
+    /// displays this value for stderr, instead of stdout.
+    pub fn for_stderr(self) -> Styled<T> {
+        self.with_support(ColorSupport::stderr())
+    }
+
+    pub fn get_ref(&self) -> &T {
+        &self.value
+    }
+
+    pub fn into_inner(self) -> T {
+        self.value
+    }
+}
+
+impl<T> Display for Styled<T>
+where
+    T: Display,
+{
+    fn fmt(&self, formatter: &mut Formatter) -> Result<(), std::fmt::Error> {
+        let support = self.support.unwrap_or_else(ColorSupport::stdout);
+        let styled =
+            support != ColorSupport::None && self.style.write_prefix(formatter, support)?;
+
+        Display::fmt(&self.value, formatter)?;
+
+        match styled {
+            true => formatter.write_str("\x1b[0m"),
+            false => Ok(()),
+        }
+    }
+}
+
+/// a hyperlink, displayed with the osc 8 escape sequence. terminals without styling support display only the text.
+///
+/// bytes of the url outside printable ascii, including control characters which would end the sequence early, are
+/// percent-encoded.
+///
+/// # examples.
+///
+/// ```
+/// # use ari::console::{self, ColorSupport};
+///
+/// let link = console::hyperlink("https://example.com", "example").with_support(ColorSupport::Basic);
+///
+/// assert_eq!(link.to_string(), "\x1b]8;;https://example.com\x1b\\example\x1b]8;;\x1b\\");
+/// assert_eq!(link.with_support(ColorSupport::None).to_string(), "example");
+///
+/// let link = console::hyperlink("https://example.com/\x1b[2J\x07é", "x").with_support(ColorSupport::Basic);
+///
+/// assert_eq!(link.to_string(), "\x1b]8;;https://example.com/%1B[2J%07%C3%A9\x1b\\x\x1b]8;;\x1b\\");
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Hyperlink<T> {
+    url: String,
+    text: T,
+    support: Option<ColorSupport>,
+}
+
+/// creates a hyperlink to `url` displaying `text`, for display on stdout. see `Hyperlink`.
+pub fn hyperlink<T>(url: impl Into<String>, text: T) -> Hyperlink<T> {
+    Hyperlink {
+        url: url.into(),
+        text,
+        support: None,
+    }
+}
+
+impl<T> Hyperlink<T> {
+    /// displays this hyperlink for a terminal with `support`, instead of the support of stdout.
+    pub fn with_support(mut self, support: ColorSupport) -> Hyperlink<T> {
+        self.support = Some(support);
+        self
+    }
+
+    /// displays this hyperlink for stderr, instead of stdout.
+    pub fn for_stderr(self) -> Hyperlink<T> {
+        self.with_support(ColorSupport::stderr())
+    }
+}
+
+impl<T> Display for Hyperlink<T>
+where
+    T: Display,
+{
+    fn fmt(&self, formatter: &mut Formatter) -> Result<(), std::fmt::Error> {
+        match self.support.unwrap_or_else(ColorSupport::stdout) {
+            ColorSupport::None => Display::fmt(&self.text, formatter),
+            _ => {
+                formatter.write_str("\x1b]8;;")?;
+
+                for byte in self.url.bytes() {
+                    match byte {
+                        0x20..=0x7e => formatter.write_char(byte as char)?,
+                        _ => write!(formatter, "%{:02X}", byte)?,
+                    }
+                }
+
+                formatter.write_str("\x1b\\")?;
+                Display::fmt(&self.text, formatter)?;
+                formatter.write_str("\x1b]8;;\x1b\\")
+            }
+        }
+    }
+}
+
+// returns the rgb value of a color in the 256 color palette.
+fn ansi256_to_rgb(index: u8) -> (u8, u8, u8) {
+    let level = |x: u8| if x == 0 { 0 } else { 55 + 40 * x };
+
+    match index {
+        0..=15 => PALETTE[index as usize],
+        16..=231 => {
+            let x = index - 16;
+
+            (level(x / 36), level(x / 6 % 6), level(x % 6))
+        }
+        _ => {
+            let gray = 8 + 10 * (index - 232);
+
+            (gray, gray, gray)
+        }
+    }
+}
+
+// returns the closest color in the 256 color palette, choosing between the color cube and the grayscale ramp.
+fn rgb_to_ansi256(r: u8, g: u8, b: u8) -> u8 {
+    let step = |x: u8| match x {
+        0..48 => 0,
+        48..115 => 1,
+        _ => (x - 35) / 40,
+    };
+
+    let cube = 16 + 36 * step(r) + 6 * step(g) + step(b);
+    let average = (r as u32 + g as u32 + b as u32) / 3;
+    let gray = 232 + (average.saturating_sub(3) / 10).min(23) as u8;
+
+    match distance((r, g, b), ansi256_to_rgb(gray)) < distance((r, g, b), ansi256_to_rgb(cube)) {
+        true => gray,
+        false => cube,
+    }
+}
+
+// returns the closest of the 16 basic colors.
+fn nearest_basic(r: u8, g: u8, b: u8) -> Color {
+    let index = (0..16)
+        .min_by_key(|x| distance((r, g, b), PALETTE[*x]))
+        .unwrap_or(0);
+
+    BASIC[index]
+}
+
+fn distance(a: (u8, u8, u8), b: (u8, u8, u8)) -> u32 {
+    let square = |x: u8, y: u8| (x as i32 - y as i32).pow(2) as u32;
+
+    square(a.0, b.0) + square(a.1, b.1) + square(a.2, b.2)
+}
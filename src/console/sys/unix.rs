@@ -0,0 +1,351 @@
+use std::mem::MaybeUninit;
+use std::sync::atomic::{AtomicBool, AtomicI32, AtomicUsize, Ordering};
+use std::sync::Once;
+use std::time::Duration;
+
+/// the maximum number of simultaneous resize subscriptions.
+const MAX_SUBSCRIBERS: usize = 16;
+
+// the write ends of the pipes of every resize subscription, or -1 for a free slot. read by the signal handler, so these
+// must be plain atomics.
+static SUBSCRIBERS: [AtomicI32; MAX_SUBSCRIBERS] = [const { AtomicI32::new(-1) }; MAX_SUBSCRIBERS];
+
+// the number of signal handlers currently writing to subscribers. a subscription is only closed once its slot has been
+// cleared and this is zero, so that a handler never writes to a descriptor which has been closed and reused.
+static ACTIVE_HANDLERS: AtomicUsize = AtomicUsize::new(0);
+
+static INSTALL_HANDLER: Once = Once::new();
+
+// the `SIGWINCH` handler installed before ours, and whether it takes `siginfo_t`. called by our handler after notifying
+// subscribers.
+static PREVIOUS_HANDLER: AtomicUsize = AtomicUsize::new(libc::SIG_DFL);
+static PREVIOUS_SIGINFO: AtomicBool = AtomicBool::new(false);
+
+/// restores the terminal attributes of stdin when dropped.
+pub(crate) struct RawModeGuard {
+    original: libc::termios,
+}
+
+impl Drop for RawModeGuard {
+    fn drop(&mut self) {
+        unsafe {
+            libc::tcsetattr(libc::STDIN_FILENO, libc::TCSANOW, &self.original);
+        }
+    }
+}
+
+/// switches stdin into raw mode, until the returned guard is dropped. stdin must be a terminal.
+pub(crate) fn enable_raw_mode() -> Result<RawModeGuard, std::io::Error> {
+    unsafe {
+        let mut attributes = MaybeUninit::<libc::termios>::uninit();
+
+        if libc::tcgetattr(libc::STDIN_FILENO, attributes.as_mut_ptr()) != 0 {
+            return Err(std::io::Error::last_os_error());
+        }
+
+        let original = attributes.assume_init();
+        let mut raw = original;
+
+        libc::cfmakeraw(&mut raw);
+
+        if libc::tcsetattr(libc::STDIN_FILENO, libc::TCSANOW, &raw) != 0 {
+            return Err(std::io::Error::last_os_error());
+        }
+
+        Ok(RawModeGuard { original })
+    }
+}
+
+/// reads available bytes from stdin, bypassing the buffering of `std::io::stdin`.
+pub(crate) fn read_input(buffer: &mut [u8]) -> Result<usize, std::io::Error> {
+    loop {
+        let read = unsafe {
+            libc::read(
+                libc::STDIN_FILENO,
+                buffer.as_mut_ptr() as *mut libc::c_void,
+                buffer.len(),
+            )
+        };
+
+        match read {
+            -1 => {
+                let error = std::io::Error::last_os_error();
+
+                if error.kind() != std::io::ErrorKind::Interrupted {
+                    return Err(error);
+                }
+            }
+            read => return Ok(read as usize),
+        }
+    }
+}
+
+/// returns the columns and rows of the terminal attached to stdout, stderr or stdin, in that order of preference.
+pub(crate) fn window_size() -> Option<(u16, u16)> {
+    for fd in [libc::STDOUT_FILENO, libc::STDERR_FILENO, libc::STDIN_FILENO] {
+        let mut size = MaybeUninit::<libc::winsize>::zeroed();
+
+        if unsafe { libc::ioctl(fd, libc::TIOCGWINSZ, size.as_mut_ptr()) } == 0 {
+            let size = unsafe { size.assume_init() };
+
+            if size.ws_col > 0 && size.ws_row > 0 {
+                return Some((size.ws_col, size.ws_row));
+            }
+        }
+    }
+
+    None
+}
+
+#[derive(Clone, Copy, Debug, Default)]
+pub(crate) struct Ready {
+    pub(crate) input: bool,
+    pub(crate) resized: bool,
+}
+
+/// waits until stdin has input or `resize` is signalled, for at most `timeout`. may return early with nothing ready if
+/// interrupted by a signal.
+pub(crate) fn wait_input(
+    timeout: Option<Duration>,
+    resize: Option<&ResizeSubscription>,
+) -> Result<Ready, std::io::Error> {
+    let mut descriptors = [
+        libc::pollfd {
+            fd: libc::STDIN_FILENO,
+            events: libc::POLLIN,
+            revents: 0,
+        },
+        libc::pollfd {
+            fd: resize.map(|x| x.read).unwrap_or(-1),
+            events: libc::POLLIN,
+            revents: 0,
+        },
+    ];
+
+    let timeout = match timeout {
+        Some(x) => x.as_nanos().div_ceil(1_000_000).min(i32::MAX as u128) as i32,
+        None => -1,
+    };
+
+    if unsafe { libc::poll(descriptors.as_mut_ptr(), 2, timeout) } == -1 {
+        let error = std::io::Error::last_os_error();
+
+        return match error.kind() {
+            std::io::ErrorKind::Interrupted => Ok(Ready::default()),
+            _ => Err(error),
+        };
+    }
+
+    Ok(Ready {
+        input: descriptors[0].revents & (libc::POLLIN | libc::POLLHUP | libc::POLLERR) != 0,
+        resized: descriptors[1].revents & libc::POLLIN != 0
+            && resize.map(|x| x.drain()).unwrap_or(false),
+    })
+}
+
+/// a subscription to terminal resize notifications, delivered by `SIGWINCH` through a self-pipe.
+pub(crate) struct ResizeSubscription {
+    slot: usize,
+    read: libc::c_int,
+    write: libc::c_int,
+}
+
+impl ResizeSubscription {
+    pub(crate) fn new() -> Result<ResizeSubscription, std::io::Error> {
+        let mut fds = [0; 2];
+
+        unsafe {
+            if libc::pipe(fds.as_mut_ptr()) != 0 {
+                return Err(std::io::Error::last_os_error());
+            }
+
+            for fd in &fds {
+                libc::fcntl(*fd, libc::F_SETFD, libc::FD_CLOEXEC);
+                libc::fcntl(
+                    *fd,
+                    libc::F_SETFL,
+                    libc::fcntl(*fd, libc::F_GETFL) | libc::O_NONBLOCK,
+                );
+            }
+        }
+
+        let slot = SUBSCRIBERS.iter().position(|x| {
+            x.compare_exchange(-1, fds[1], Ordering::AcqRel, Ordering::Acquire)
+                .is_ok()
+        });
+
+        let slot = match slot {
+            Some(slot) => slot,
+            None => {
+                unsafe {
+                    libc::close(fds[0]);
+                    libc::close(fds[1]);
+                }
+
+                return Err(std::io::Error::other("too many resize subscriptions"));
+            }
+        };
+
+        INSTALL_HANDLER.call_once(|| unsafe {
+            let mut action = MaybeUninit::<libc::sigaction>::zeroed().assume_init();
+            let mut previous = MaybeUninit::<libc::sigaction>::zeroed().assume_init();
+
+            action.sa_sigaction = on_sigwinch as SigInfoHandler as libc::sighandler_t;
+            action.sa_flags = libc::SA_RESTART | libc::SA_SIGINFO;
+            libc::sigemptyset(&mut action.sa_mask);
+
+            if libc::sigaction(libc::SIGWINCH, &action, &mut previous) == 0 {
+                PREVIOUS_SIGINFO
+                    .store(previous.sa_flags & libc::SA_SIGINFO != 0, Ordering::Release);
+                PREVIOUS_HANDLER.store(previous.sa_sigaction, Ordering::Release);
+            }
+        });
+
+        Ok(ResizeSubscription {
+            slot,
+            read: fds[0],
+            write: fds[1],
+        })
+    }
+
+    /// waits for a notification, for at most `timeout`, returning true if there was one.
+    pub(crate) fn wait(&self, timeout: Option<Duration>) -> Result<bool, std::io::Error> {
+        let mut descriptor = libc::pollfd {
+            fd: self.read,
+            events: libc::POLLIN,
+            revents: 0,
+        };
+
+        let timeout = match timeout {
+            Some(x) => x.as_nanos().div_ceil(1_000_000).min(i32::MAX as u128) as i32,
+            None => -1,
+        };
+
+        if unsafe { libc::poll(&mut descriptor, 1, timeout) } == -1 {
+            let error = std::io::Error::last_os_error();
+
+            return match error.kind() {
+                std::io::ErrorKind::Interrupted => Ok(false),
+                _ => Err(error),
+            };
+        }
+
+        Ok(descriptor.revents & libc::POLLIN != 0 && self.drain())
+    }
+
+    /// wakes a thread blocked in `wait`, as if a notification had been delivered.
+    pub(crate) fn wake(&self) {
+        unsafe {
+            libc::write(self.write, b"\x01".as_ptr() as *const libc::c_void, 1);
+        }
+    }
+
+    /// consumes pending notifications, returning true if there were any.
+    pub(crate) fn drain(&self) -> bool {
+        let mut buffer = [0u8; 64];
+        let mut signalled = false;
+
+        while unsafe {
+            libc::read(
+                self.read,
+                buffer.as_mut_ptr() as *mut libc::c_void,
+                buffer.len(),
+            )
+        } > 0
+        {
+            signalled = true;
+        }
+
+        signalled
+    }
+}
+
+impl Drop for ResizeSubscription {
+    fn drop(&mut self) {
+        SUBSCRIBERS[self.slot].store(-1, Ordering::SeqCst);
+
+        // a handler which read the descriptor before it was cleared may still be writing to it.
+        while ACTIVE_HANDLERS.load(Ordering::SeqCst) > 0 {
+            std::hint::spin_loop();
+        }
+
+        unsafe {
+            libc::close(self.read);
+            libc::close(self.write);
+        }
+    }
+}
+
+type SigInfoHandler = extern "C" fn(libc::c_int, *mut libc::siginfo_t, *mut libc::c_void);
+
+// notifies every subscriber, then calls the previously installed handler. errno is preserved, so that the interrupted
+// code does not observe the errors of our writes.
+extern "C" fn on_sigwinch(
+    signal: libc::c_int,
+    info: *mut libc::siginfo_t,
+    context: *mut libc::c_void,
+) {
+    let errno = unsafe { *errno_location() };
+
+    ACTIVE_HANDLERS.fetch_add(1, Ordering::SeqCst);
+
+    for subscriber in &SUBSCRIBERS {
+        let fd = subscriber.load(Ordering::SeqCst);
+
+        if fd >= 0 {
+            unsafe {
+                libc::write(fd, b"\x01".as_ptr() as *const libc::c_void, 1);
+            }
+        }
+    }
+
+    ACTIVE_HANDLERS.fetch_sub(1, Ordering::SeqCst);
+
+    match PREVIOUS_HANDLER.load(Ordering::Acquire) {
+        libc::SIG_DFL | libc::SIG_IGN => {}
+        handler => unsafe {
+            match PREVIOUS_SIGINFO.load(Ordering::Acquire) {
+                true => std::mem::transmute::<libc::sighandler_t, SigInfoHandler>(handler)(
+                    signal, info, context,
+                ),
+                false => std::mem::transmute::<libc::sighandler_t, extern "C" fn(libc::c_int)>(
+                    handler,
+                )(signal),
+            }
+        },
+    }
+
+    unsafe {
+        *errno_location() = errno;
+    }
+}
+
+#[cfg(any(
+    target_os = "linux",
+    target_os = "emscripten",
+    target_os = "dragonfly",
+    target_os = "redox"
+))]
+unsafe fn errno_location() -> *mut libc::c_int {
+    libc::__errno_location()
+}
+
+#[cfg(any(target_os = "macos", target_os = "ios", target_os = "freebsd"))]
+unsafe fn errno_location() -> *mut libc::c_int {
+    libc::__error()
+}
+
+#[cfg(any(target_os = "android", target_os = "openbsd", target_os = "netbsd"))]
+unsafe fn errno_location() -> *mut libc::c_int {
+    libc::__errno()
+}
+
+#[cfg(any(target_os = "solaris", target_os = "illumos"))]
+unsafe fn errno_location() -> *mut libc::c_int {
+    libc::___errno()
+}
+
+#[cfg(target_os = "haiku")]
+unsafe fn errno_location() -> *mut libc::c_int {
+    libc::_errnop()
+}
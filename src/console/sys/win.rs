@@ -0,0 +1,233 @@
+use std::time::{Duration, Instant};
+use winapi::shared::minwindef::{DWORD, TRUE};
+use winapi::shared::winerror::WAIT_TIMEOUT;
+use winapi::um::consoleapi::{GetConsoleMode, ReadConsoleInputW, ReadConsoleW, SetConsoleMode};
+use winapi::um::processenv::GetStdHandle;
+use winapi::um::synchapi::WaitForSingleObject;
+use winapi::um::winbase::{
+    INFINITE, STD_ERROR_HANDLE, STD_INPUT_HANDLE, STD_OUTPUT_HANDLE, WAIT_OBJECT_0,
+};
+use winapi::um::wincon::{
+    GetConsoleScreenBufferInfo, PeekConsoleInputW, CONSOLE_SCREEN_BUFFER_INFO, ENABLE_ECHO_INPUT,
+    ENABLE_LINE_INPUT, ENABLE_PROCESSED_INPUT, ENABLE_VIRTUAL_TERMINAL_INPUT,
+};
+use winapi::um::wincontypes::{INPUT_RECORD, KEY_EVENT};
+use winapi::um::winnt::HANDLE;
+
+/// the interval at which `ResizeSubscription::wait` sleeps when it has no timeout.
+const POLL_INTERVAL: Duration = Duration::from_millis(10);
+
+/// the number of console input records examined at once.
+const PEEK_RECORDS: usize = 32;
+
+/// restores the console mode of stdin when dropped.
+pub(crate) struct RawModeGuard {
+    handle: HANDLE,
+    original: DWORD,
+}
+
+impl Drop for RawModeGuard {
+    fn drop(&mut self) {
+        unsafe {
+            SetConsoleMode(self.handle, self.original);
+        }
+    }
+}
+
+/// switches stdin into raw mode, until the returned guard is dropped. input is delivered as virtual terminal
+/// sequences, so that it may be decoded exactly as on unix. stdin must be a console.
+pub(crate) fn enable_raw_mode() -> Result<RawModeGuard, std::io::Error> {
+    unsafe {
+        let handle = GetStdHandle(STD_INPUT_HANDLE);
+        let mut original: DWORD = 0;
+
+        if GetConsoleMode(handle, &mut original) != TRUE {
+            return Err(std::io::Error::last_os_error());
+        }
+
+        let raw = (original & !(ENABLE_ECHO_INPUT | ENABLE_LINE_INPUT | ENABLE_PROCESSED_INPUT))
+            | ENABLE_VIRTUAL_TERMINAL_INPUT;
+
+        if SetConsoleMode(handle, raw) != TRUE {
+            return Err(std::io::Error::last_os_error());
+        }
+
+        Ok(RawModeGuard { handle, original })
+    }
+}
+
+/// reads available input from the console attached to stdin, encoded as utf-8. `buffer` must hold at least 8 bytes.
+///
+/// the console is read directly rather than through `std::io::stdin`, whose buffer could hold input which `wait_input`
+/// cannot see.
+pub(crate) fn read_input(buffer: &mut [u8]) -> Result<usize, std::io::Error> {
+    // every utf-16 unit encodes to at most 3 bytes of utf-8. one unit is reserved to complete a surrogate pair.
+    let capacity = (buffer.len() / 4).saturating_sub(1).max(1);
+    let mut units = vec![0u16; capacity + 1];
+    let mut read = read_console(&mut units[..capacity])?;
+
+    if read > 0 && (0xd800..0xdc00).contains(&units[read - 1]) {
+        read += read_console(&mut units[read..read + 1])?;
+    }
+
+    let mut length = 0;
+
+    for character in std::char::decode_utf16(units[..read].iter().copied()) {
+        let character = character.unwrap_or(char::REPLACEMENT_CHARACTER);
+
+        length += character.encode_utf8(&mut buffer[length..]).len();
+    }
+
+    Ok(length)
+}
+
+// reads utf-16 units from the console attached to stdin, blocking until at least one is available.
+fn read_console(units: &mut [u16]) -> Result<usize, std::io::Error> {
+    let mut read: DWORD = 0;
+
+    if unsafe {
+        ReadConsoleW(
+            GetStdHandle(STD_INPUT_HANDLE),
+            units.as_mut_ptr() as *mut _,
+            units.len() as DWORD,
+            &mut read,
+            std::ptr::null_mut(),
+        )
+    } != TRUE
+    {
+        return Err(std::io::Error::last_os_error());
+    }
+
+    Ok(read as usize)
+}
+
+#[derive(Clone, Copy, Debug, Default)]
+pub(crate) struct Ready {
+    pub(crate) input: bool,
+    pub(crate) resized: bool,
+}
+
+/// waits until stdin has input, for at most `timeout`.
+///
+/// records which produce no bytes when read, such as focus, mouse, buffer size and key-up records, are removed from the
+/// console input buffer while waiting, so that a subsequent read does not block.
+pub(crate) fn wait_input(
+    timeout: Option<Duration>,
+    _resize: Option<&ResizeSubscription>,
+) -> Result<Ready, std::io::Error> {
+    let handle = unsafe { GetStdHandle(STD_INPUT_HANDLE) };
+    let deadline = timeout.map(|x| Instant::now() + x);
+
+    loop {
+        let milliseconds = match deadline {
+            Some(x) => x
+                .saturating_duration_since(Instant::now())
+                .as_nanos()
+                .div_ceil(1_000_000)
+                .min((INFINITE - 1) as u128) as DWORD,
+            None => INFINITE,
+        };
+
+        match unsafe { WaitForSingleObject(handle, milliseconds) } {
+            WAIT_OBJECT_0 => {}
+            WAIT_TIMEOUT => return Ok(Ready::default()),
+            _ => return Err(std::io::Error::last_os_error()),
+        }
+
+        if discard_records(handle)? {
+            return Ok(Ready {
+                input: true,
+                resized: false,
+            });
+        }
+    }
+}
+
+// removes records which produce no bytes from the front of the console input buffer, returning true if a record which
+// does produce bytes remains.
+fn discard_records(handle: HANDLE) -> Result<bool, std::io::Error> {
+    let mut records = unsafe { std::mem::zeroed::<[INPUT_RECORD; PEEK_RECORDS]>() };
+    let mut count: DWORD = 0;
+
+    if unsafe {
+        PeekConsoleInputW(
+            handle,
+            records.as_mut_ptr(),
+            PEEK_RECORDS as DWORD,
+            &mut count,
+        )
+    } != TRUE
+    {
+        return Err(std::io::Error::last_os_error());
+    }
+
+    let count = count as usize;
+    let skip = records[..count]
+        .iter()
+        .take_while(|x| !produces_input(x))
+        .count();
+
+    if skip > 0 {
+        let mut read: DWORD = 0;
+
+        if unsafe { ReadConsoleInputW(handle, records.as_mut_ptr(), skip as DWORD, &mut read) }
+            != TRUE
+        {
+            return Err(std::io::Error::last_os_error());
+        }
+    }
+
+    Ok(skip < count)
+}
+
+// returns true if `record` is a key press which produces a character, including those of virtual terminal sequences.
+fn produces_input(record: &INPUT_RECORD) -> bool {
+    if record.EventType != KEY_EVENT {
+        return false;
+    }
+
+    unsafe {
+        let key = record.Event.KeyEvent();
+
+        key.bKeyDown == TRUE && *key.uChar.UnicodeChar() != 0
+    }
+}
+
+/// a subscription to terminal resize notifications. windows has no resize signal, so this never fires.
+pub(crate) struct ResizeSubscription;
+
+impl ResizeSubscription {
+    pub(crate) fn new() -> Result<ResizeSubscription, std::io::Error> {
+        Ok(ResizeSubscription)
+    }
+
+    /// sleeps for `timeout`. resizes must instead be detected by polling `window_size`.
+    pub(crate) fn wait(&self, timeout: Option<Duration>) -> Result<bool, std::io::Error> {
+        std::thread::sleep(timeout.unwrap_or(POLL_INTERVAL));
+
+        Ok(false)
+    }
+
+    pub(crate) fn wake(&self) {}
+}
+
+/// returns the columns and rows of the visible window of the console attached to stdout or stderr.
+pub(crate) fn window_size() -> Option<(u16, u16)> {
+    for handle in [STD_OUTPUT_HANDLE, STD_ERROR_HANDLE] {
+        unsafe {
+            let mut info = std::mem::zeroed::<CONSOLE_SCREEN_BUFFER_INFO>();
+
+            if GetConsoleScreenBufferInfo(GetStdHandle(handle), &mut info) != 0 {
+                let window = info.srWindow;
+                let columns = (window.Right - window.Left + 1) as u16;
+                let rows = (window.Bottom - window.Top + 1) as u16;
+
+                if columns > 0 && rows > 0 {
+                    return Some((columns, rows));
+                }
+            }
+        }
+    }
+
+    None
+}
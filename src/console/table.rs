@@ -0,0 +1,363 @@
+use std::borrow::Cow;
+use std::fmt::{Display, Formatter};
+use std::io::Write;
+
+use crate::str::TextAlignment;
+
+/// the borders drawn around and between the cells of a `Table`.
+///
+/// # examples.
+///
+/// ```
+/// # use ari::console::{Table, TableStyle};
+///
+/// let mut table = Table::new(["flag", "meaning"]).with_style(TableStyle::Markdown);
+///
+/// table.add_row(["a|b", "either a\nor b"]);
+///
+/// assert_eq!(
+///     table.to_string(),
+///     "| flag | meaning        |\n\
+///      |:-----|:---------------|\n\
+///      | a\\|b | either a\\nor b |\n"
+/// );
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TableStyle {
+    /// columns separated by two spaces, without any borders.
+    Plain,
+
+    /// borders drawn with `+`, `-` and `|`.
+    Ascii,
+
+    /// borders drawn with unicode box-drawing characters.
+    Box,
+
+    /// a github-flavoured markdown table, with alignment markers below the header. `|` within cells is escaped as `\|`.
+    Markdown,
+}
+
+/// a table of text, rendered with aligned columns through its `Display` implementation.
+///
+/// tabs and line breaks within cells are rendered as `\t`, `\n` and `\r`, so that every row is a single line.
+///
+/// column widths are measured in terminal columns, ignoring escape sequences, so wide characters and styled cells line
+/// up. when a maximum width is set, the widest columns are narrowed until the table fits, and cells that no longer fit
+/// are truncated with an ellipsis.
+///
+/// # examples.
+///
+/// ```
+/// # use ari::console::{Table, TableStyle};
+/// # use ari::str::TextAlignment;
+///
+/// let mut table = Table::new(["name", "size"])
+///     .with_style(TableStyle::Ascii)
+///     .with_alignment(1, TextAlignment::Right);
+///
+/// table.add_row(["readme.md", "1.2 KiB"]);
+/// table.add_row(["ari.tar.gz", "96.0 MiB"]);
+///
+/// assert_eq!(
+///     table.to_string(),
+///     "+------------+----------+\n\
+///      | name       |     size |\n\
+///      +------------+----------+\n\
+///      | readme.md  |  1.2 KiB |\n\
+///      | ari.tar.gz | 96.0 MiB |\n\
+///      +------------+----------+\n"
+/// );
+///
+/// let table = table.with_style(TableStyle::Plain).with_max_width(16);
+///
+/// assert_eq!(
+///     table.to_string(),
+///     "name        size\n\
+///      readme…  1.2 KiB\n\
+///      ari.ta…  96.0 M…\n"
+/// );
+///
+/// assert_eq!(table.to_csv(), "name,size\nreadme.md,1.2 KiB\nari.tar.gz,96.0 MiB\n");
+/// ```
+#[derive(Clone, Debug)]
+pub struct Table {
+    headers: Vec<String>,
+    rows: Vec<Vec<String>>,
+    alignments: Vec<TextAlignment>,
+    style: TableStyle,
+    max_width: Option<usize>,
+}
+
+impl Table {
+    /// creates an empty table with the given column headers.
+    pub fn new<T: Into<String>>(headers: impl IntoIterator<Item = T>) -> Table {
+        Table {
+            headers: headers.into_iter().map(|x| x.into()).collect(),
+            rows: vec![],
+            alignments: vec![],
+            style: TableStyle::Box,
+            max_width: None,
+        }
+    }
+
+    /// sets the borders of this table. defaults to `TableStyle::Box`.
+    pub fn with_style(mut self, style: TableStyle) -> Table {
+        self.style = style;
+        self
+    }
+
+    /// sets the alignment of the zero-based `column`. columns are left-aligned by default.
+    pub fn with_alignment(mut self, column: usize, alignment: TextAlignment) -> Table {
+        if self.alignments.len() <= column {
+            self.alignments.resize(column + 1, TextAlignment::Left);
+        }
+
+        self.alignments[column] = alignment;
+        self
+    }
+
+    /// sets the maximum width of every rendered line, in terminal columns.
+    pub fn with_max_width(mut self, width: usize) -> Table {
+        self.max_width = Some(width);
+        self
+    }
+
+    /// limits the width of this table to that of the terminal, if it is known. see `console::size`.
+    pub fn fit_to_terminal(self) -> Table {
+        match crate::console::size() {
+            Some(size) => self.with_max_width(size.columns as usize),
+            None => self,
+        }
+    }
+
+    /// appends a row. rows with fewer cells than the table has columns are padded with empty cells.
+    pub fn add_row<T: Into<String>>(&mut self, cells: impl IntoIterator<Item = T>) {
+        self.rows
+            .push(cells.into_iter().map(|x| x.into()).collect());
+    }
+
+    pub fn headers(&self) -> &[String] {
+        &self.headers
+    }
+
+    pub fn rows(&self) -> &[Vec<String>] {
+        &self.rows
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rows.is_empty()
+    }
+
+    /// writes the headers and rows of this table as comma-separated values, quoting cells as described in rfc 4180.
+    pub fn write_csv(&self, output: &mut impl Write) -> Result<(), std::io::Error> {
+        self.write_separated(output, ",", |cell| {
+            match cell.contains([',', '"', '\r', '\n']) {
+                true => format!("\"{}\"", cell.replace('"', "\"\"")),
+                false => cell.to_owned(),
+            }
+        })
+    }
+
+    /// writes the headers and rows of this table as tab-separated values. tabs, line breaks and backslashes within
+    /// cells are escaped as `\t`, `\n`, `\r` and `\\`.
+    pub fn write_tsv(&self, output: &mut impl Write) -> Result<(), std::io::Error> {
+        self.write_separated(output, "\t", |cell| {
+            cell.replace('\\', "\\\\")
+                .replace('\t', "\\t")
+                .replace('\n', "\\n")
+                .replace('\r', "\\r")
+        })
+    }
+
+    pub fn to_csv(&self) -> String {
+        let mut output = vec![];
+
+        self.write_csv(&mut output)
+            .expect("writing to a vec cannot fail");
+        String::from_utf8(output).expect("cells are valid utf-8")
+    }
+
+    pub fn to_tsv(&self) -> String {
+        let mut output = vec![];
+
+        self.write_tsv(&mut output)
+            .expect("writing to a vec cannot fail");
+        String::from_utf8(output).expect("cells are valid utf-8")
+    }
+
+    fn write_separated(
+        &self,
+        output: &mut impl Write,
+        separator: &str,
+        escape: impl Fn(&str) -> String,
+    ) -> Result<(), std::io::Error> {
+        let columns = self.columns();
+
+        for row in std::iter::once(&self.headers).chain(&self.rows) {
+            let cells = (0..columns)
+                .map(|i| escape(row.get(i).map(|x| x.as_str()).unwrap_or("")))
+                .collect::<Vec<_>>();
+
+            writeln!(output, "{}", cells.join(separator))?;
+        }
+
+        Ok(())
+    }
+
+    fn columns(&self) -> usize {
+        std::iter::once(&self.headers)
+            .chain(&self.rows)
+            .map(|x| x.len())
+            .max()
+            .unwrap_or(0)
+    }
+
+    fn alignment(&self, column: usize) -> TextAlignment {
+        self.alignments
+            .get(column)
+            .copied()
+            .unwrap_or(TextAlignment::Left)
+    }
+
+    // computes the width of every column, narrowing the widest columns until the table fits within `max_width`.
+    fn widths(&self) -> Vec<usize> {
+        let columns = self.columns();
+        let mut widths = vec![0; columns];
+
+        for row in std::iter::once(&self.headers).chain(&self.rows) {
+            for (i, cell) in row.iter().enumerate() {
+                widths[i] = widths[i].max(crate::str::display_width(&self.escape(cell)));
+            }
+        }
+
+        if let Some(max_width) = self.max_width {
+            let overhead = match self.style {
+                TableStyle::Plain => 2 * columns.saturating_sub(1),
+                TableStyle::Ascii | TableStyle::Box | TableStyle::Markdown => 3 * columns + 1,
+            };
+
+            while widths.iter().sum::<usize>() + overhead > max_width {
+                match widths.iter_mut().max() {
+                    Some(widest) if *widest > 1 => *widest -= 1,
+                    _ => break,
+                }
+            }
+        }
+
+        widths
+    }
+
+    // escapes the characters of `cell` which would break the layout of this table's style.
+    fn escape<'a>(&self, cell: &'a str) -> Cow<'a, str> {
+        let markdown = self.style == TableStyle::Markdown;
+
+        if !(cell.contains(['\t', '\n', '\r']) || (markdown && cell.contains('|'))) {
+            return Cow::Borrowed(cell);
+        }
+
+        let mut escaped = String::with_capacity(cell.len() + 2);
+
+        for character in cell.chars() {
+            match character {
+                '\t' => escaped.push_str("\\t"),
+                '\n' => escaped.push_str("\\n"),
+                '\r' => escaped.push_str("\\r"),
+                '|' if markdown => escaped.push_str("\\|"),
+                character => escaped.push(character),
+            }
+        }
+
+        Cow::Owned(escaped)
+    }
+
+    fn ellipsis(&self) -> &'static str {
+        match self.style {
+            TableStyle::Ascii => "...",
+            TableStyle::Plain | TableStyle::Box | TableStyle::Markdown => "…",
+        }
+    }
+}
+
+impl Display for Table {
+    fn fmt(&self, formatter: &mut Formatter) -> Result<(), std::fmt::Error> {
+        let widths = self.widths();
+
+        if widths.is_empty() {
+            return Ok(());
+        }
+
+        let (left, middle, right) = match self.style {
+            TableStyle::Plain => ("", "  ", ""),
+            TableStyle::Ascii | TableStyle::Markdown => ("| ", " | ", " |"),
+            TableStyle::Box => ("│ ", " │ ", " │"),
+        };
+
+        let rule = |formatter: &mut Formatter, [left, middle, right, line]: [&str; 4]| {
+            let segments = widths
+                .iter()
+                .map(|x| line.repeat(x + 2))
+                .collect::<Vec<_>>();
+
+            writeln!(formatter, "{}{}{}", left, segments.join(middle), right)
+        };
+
+        let row = |formatter: &mut Formatter, cells: &[String]| {
+            let cells = widths
+                .iter()
+                .enumerate()
+                .map(|(i, width)| {
+                    let cell = self.escape(cells.get(i).map(|x| x.as_str()).unwrap_or(""));
+                    let cell = crate::str::truncate_ansi(&cell, *width, self.ellipsis());
+
+                    crate::str::pad_ansi(&cell, *width, ' ', self.alignment(i))
+                })
+                .collect::<Vec<_>>();
+
+            let line = format!("{}{}{}", left, cells.join(middle), right);
+
+            match self.style {
+                TableStyle::Plain => writeln!(formatter, "{}", line.trim_end()),
+                _ => writeln!(formatter, "{}", line),
+            }
+        };
+
+        match self.style {
+            TableStyle::Plain => {
+                row(formatter, &self.headers)?;
+            }
+            TableStyle::Ascii => {
+                rule(formatter, ["+", "+", "+", "-"])?;
+                row(formatter, &self.headers)?;
+                rule(formatter, ["+", "+", "+", "-"])?;
+            }
+            TableStyle::Box => {
+                rule(formatter, ["┌", "┬", "┐", "─"])?;
+                row(formatter, &self.headers)?;
+                rule(formatter, ["├", "┼", "┤", "─"])?;
+            }
+            TableStyle::Markdown => {
+                let markers = widths
+                    .iter()
+                    .enumerate()
+                    .map(|(i, width)| match self.alignment(i) {
+                        TextAlignment::Left => format!(":{}", "-".repeat(width + 1)),
+                        TextAlignment::Right => format!("{}:", "-".repeat(width + 1)),
+                    })
+                    .collect::<Vec<_>>();
+
+                row(formatter, &self.headers)?;
+                writeln!(formatter, "|{}|", markers.join("|"))?;
+            }
+        }
+
+        for cells in &self.rows {
+            row(formatter, cells)?;
+        }
+
+        match self.style {
+            TableStyle::Ascii => rule(formatter, ["+", "+", "+", "-"]),
+            TableStyle::Box => rule(formatter, ["└", "┴", "┘", "─"]),
+            TableStyle::Plain | TableStyle::Markdown => Ok(()),
+        }
+    }
+}
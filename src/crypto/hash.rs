@@ -2,6 +2,7 @@ use ring::digest::{Algorithm, Context, Digest};
 use std::io::Read;
 use std::ops::Deref;
 
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 pub enum HashAlgorithm {
     Sha1,
     Sha256,
@@ -0,0 +1,84 @@
+use crate::fmt::encoding::{decode_table, Bits, Engine};
+
+const RFC4648_ALPHABET: &[u8; 32] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZ234567";
+const CROCKFORD_ALPHABET: &[u8; 32] = b"0123456789ABCDEFGHJKMNPQRSTVWXYZ";
+const RFC4648_DECODE: [u8; 256] = decode_table(RFC4648_ALPHABET, true);
+
+// crockford's alphabet also decodes the easily confused `o`, `i` and `l` as the digits they resemble.
+const CROCKFORD_DECODE: [u8; 256] = {
+    let mut table = decode_table(CROCKFORD_ALPHABET, true);
+
+    table[b'O' as usize] = 0;
+    table[b'o' as usize] = 0;
+    table[b'I' as usize] = 1;
+    table[b'i' as usize] = 1;
+    table[b'L' as usize] = 1;
+    table[b'l' as usize] = 1;
+    table
+};
+
+/// a base32 encoding. decoding is case-insensitive.
+///
+/// # examples.
+///
+/// ```
+/// # use ari::fmt::Base32;
+///
+/// assert_eq!(Base32::RFC4648.encode(b"hello"), "NBSWY3DP");
+/// assert_eq!(Base32::RFC4648.encode(b"hello!"), "NBSWY3DPEE======");
+/// assert_eq!(Base32::RFC4648_NO_PAD.encode(b"hello!"), "NBSWY3DPEE");
+/// assert_eq!(Base32::RFC4648.decode("nbswy3dp").unwrap(), b"hello");
+///
+/// assert_eq!(Base32::CROCKFORD.encode(b"hello!"), "D1JPRV3F44");
+/// assert_eq!(Base32::CROCKFORD.decode("DIJP-RV3F-44").unwrap(), b"hello!");
+/// ```
+#[derive(Clone, Copy, Debug)]
+pub struct Base32 {
+    encoding: Bits,
+}
+
+impl Base32 {
+    /// the alphabet of rfc 4648, `A` to `Z` and `2` to `7`, with padding.
+    pub const RFC4648: Base32 = Base32::new(RFC4648_ALPHABET, &RFC4648_DECODE, true, None);
+
+    /// the alphabet of rfc 4648, `A` to `Z` and `2` to `7`, without padding.
+    pub const RFC4648_NO_PAD: Base32 = Base32::new(RFC4648_ALPHABET, &RFC4648_DECODE, false, None);
+
+    /// douglas crockford's alphabet, which excludes `I`, `L`, `O` and `U`, without padding. when decoding, hyphens are
+    /// ignored, and `I`, `L` and `O` are read as `1` and `0`.
+    pub const CROCKFORD: Base32 =
+        Base32::new(CROCKFORD_ALPHABET, &CROCKFORD_DECODE, false, Some(b'-'));
+
+    const fn new(
+        alphabet: &'static [u8],
+        decode: &'static [u8; 256],
+        padding: bool,
+        separator: Option<u8>,
+    ) -> Base32 {
+        Base32 {
+            encoding: Bits {
+                bits: 5,
+                alphabet,
+                decode,
+                padding,
+                separator,
+            },
+        }
+    }
+
+    fn engine(&self) -> Engine {
+        Engine::Bits(self.encoding)
+    }
+}
+
+encoding_methods!(Base32);
+
+/// encodes `data` as padded base32, with the alphabet of rfc 4648.
+pub fn to_base32(data: &[u8]) -> String {
+    Base32::RFC4648.encode(data)
+}
+
+/// decodes padded base32 with the alphabet of rfc 4648, ignoring whitespace.
+pub fn from_base32(text: &str) -> Result<Vec<u8>, crate::fmt::DecodeError> {
+    Base32::RFC4648.decode(text)
+}
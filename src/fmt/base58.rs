@@ -0,0 +1,55 @@
+use crate::fmt::encoding::{decode_table, Engine, Number};
+
+const BITCOIN_ALPHABET: &[u8; 58] = b"123456789ABCDEFGHJKLMNPQRSTUVWXYZabcdefghijkmnopqrstuvwxyz";
+const BITCOIN_DECODE: [u8; 256] = decode_table(BITCOIN_ALPHABET, false);
+
+/// a base58 encoding, which writes data as one big number, and each leading zero byte as the first character of the
+/// alphabet.
+///
+/// encoding and decoding take quadratic time in the length of the data, so base58 is best kept to short values such as
+/// keys and hashes.
+///
+/// # examples.
+///
+/// ```
+/// # use ari::fmt::{Base58, DecodeError};
+///
+/// assert_eq!(Base58::BITCOIN.encode(b"hello world"), "StV1DL6CwTryKyV");
+/// assert_eq!(Base58::BITCOIN.encode(&[0, 0, 1]), "112");
+/// assert_eq!(Base58::BITCOIN.decode("112").unwrap(), [0, 0, 1]);
+///
+/// assert_eq!(
+///     Base58::BITCOIN.decode("StV1DL0"),
+///     Err(DecodeError::InvalidCharacter { offset: 6, character: '0' })
+/// );
+/// ```
+#[derive(Clone, Copy, Debug)]
+pub struct Base58 {
+    encoding: Number,
+}
+
+impl Base58 {
+    /// the alphabet used by bitcoin, which excludes `0`, `O`, `I` and `l`.
+    pub const BITCOIN: Base58 = Base58 {
+        encoding: Number {
+            alphabet: BITCOIN_ALPHABET,
+            decode: &BITCOIN_DECODE,
+        },
+    };
+
+    fn engine(&self) -> Engine {
+        Engine::Number(self.encoding)
+    }
+}
+
+encoding_methods!(Base58);
+
+/// encodes `data` as base58, with the bitcoin alphabet.
+pub fn to_base58(data: &[u8]) -> String {
+    Base58::BITCOIN.encode(data)
+}
+
+/// decodes base58 with the bitcoin alphabet, ignoring whitespace.
+pub fn from_base58(text: &str) -> Result<Vec<u8>, crate::fmt::DecodeError> {
+    Base58::BITCOIN.decode(text)
+}
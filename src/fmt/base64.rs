@@ -0,0 +1,74 @@
+use crate::fmt::encoding::{decode_table, Bits, Engine};
+
+const STANDARD_ALPHABET: &[u8; 64] =
+    b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+const URL_SAFE_ALPHABET: &[u8; 64] =
+    b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-_";
+const STANDARD_DECODE: [u8; 256] = decode_table(STANDARD_ALPHABET, false);
+const URL_SAFE_DECODE: [u8; 256] = decode_table(URL_SAFE_ALPHABET, false);
+
+/// a base64 encoding, as described in rfc 4648.
+///
+/// encodings with padding require it when decoding, and encodings without padding reject it.
+///
+/// # examples.
+///
+/// ```
+/// # use ari::fmt::{Base64, DecodeError};
+///
+/// assert_eq!(Base64::STANDARD.encode(b"\xfb\xff"), "+/8=");
+/// assert_eq!(Base64::URL_SAFE_NO_PAD.encode(b"\xfb\xff"), "-_8");
+/// assert_eq!(Base64::URL_SAFE_NO_PAD.display(b"\xfb\xff").to_string(), "-_8");
+///
+/// assert_eq!(Base64::STANDARD.decode("aGVs\nbG8=").unwrap(), b"hello");
+/// assert_eq!(
+///     Base64::STANDARD.decode("aGVs*G8="),
+///     Err(DecodeError::InvalidCharacter { offset: 4, character: '*' })
+/// );
+/// ```
+#[derive(Clone, Copy, Debug)]
+pub struct Base64 {
+    encoding: Bits,
+}
+
+impl Base64 {
+    /// the standard alphabet, with `+` and `/`, and padding.
+    pub const STANDARD: Base64 = Base64::new(STANDARD_ALPHABET, &STANDARD_DECODE, true);
+
+    /// the standard alphabet, with `+` and `/`, and no padding.
+    pub const STANDARD_NO_PAD: Base64 = Base64::new(STANDARD_ALPHABET, &STANDARD_DECODE, false);
+
+    /// the url and filename safe alphabet, with `-` and `_`, and padding.
+    pub const URL_SAFE: Base64 = Base64::new(URL_SAFE_ALPHABET, &URL_SAFE_DECODE, true);
+
+    /// the url and filename safe alphabet, with `-` and `_`, and no padding.
+    pub const URL_SAFE_NO_PAD: Base64 = Base64::new(URL_SAFE_ALPHABET, &URL_SAFE_DECODE, false);
+
+    const fn new(alphabet: &'static [u8], decode: &'static [u8; 256], padding: bool) -> Base64 {
+        Base64 {
+            encoding: Bits {
+                bits: 6,
+                alphabet,
+                decode,
+                padding,
+                separator: None,
+            },
+        }
+    }
+
+    fn engine(&self) -> Engine {
+        Engine::Bits(self.encoding)
+    }
+}
+
+encoding_methods!(Base64);
+
+/// encodes `data` as padded base64, with the standard alphabet.
+pub fn to_base64(data: &[u8]) -> String {
+    Base64::STANDARD.encode(data)
+}
+
+/// decodes padded base64 with the standard alphabet, ignoring whitespace.
+pub fn from_base64(text: &str) -> Result<Vec<u8>, crate::fmt::DecodeError> {
+    Base64::STANDARD.decode(text)
+}
@@ -0,0 +1,725 @@
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+use std::io::{ErrorKind, Read, Write};
+
+/// marks bytes outside of an alphabet in a decode table.
+pub(crate) const INVALID: u8 = 0xff;
+
+/// the size of the chunks a `DecodingReader` reads from its inner stream.
+const READ_CHUNK: usize = 4096;
+
+/// an error produced when decoding base64, base32 or base58 text. offsets are in bytes from the start of the input.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DecodeError {
+    /// a character outside of the alphabet.
+    InvalidCharacter { offset: usize, character: char },
+
+    /// padding which is misplaced, of the wrong length, or missing.
+    InvalidPadding { offset: usize },
+
+    /// the input ends partway through a group of characters which cannot encode a whole number of bytes.
+    InvalidLength { offset: usize },
+
+    /// the last character encodes non-zero bits beyond the end of the data, so the input is not canonical.
+    TrailingBits { offset: usize },
+}
+
+impl DecodeError {
+    /// returns the offset of the offending character, or of the end of the input.
+    pub fn offset(&self) -> usize {
+        match *self {
+            DecodeError::InvalidCharacter { offset, .. } => offset,
+            DecodeError::InvalidPadding { offset } => offset,
+            DecodeError::InvalidLength { offset } => offset,
+            DecodeError::TrailingBits { offset } => offset,
+        }
+    }
+
+    // replaces the placeholder character of an invalid, non-ascii character with the real one from `text`.
+    fn with_text(self, text: &str) -> DecodeError {
+        match self {
+            DecodeError::InvalidCharacter { offset, .. } => DecodeError::InvalidCharacter {
+                offset,
+                character: text[offset..]
+                    .chars()
+                    .next()
+                    .unwrap_or(char::REPLACEMENT_CHARACTER),
+            },
+            error => error,
+        }
+    }
+}
+
+impl Display for DecodeError {
+    fn fmt(&self, formatter: &mut Formatter) -> Result<(), std::fmt::Error> {
+        match self {
+            DecodeError::InvalidCharacter { offset, character } => {
+                write!(
+                    formatter,
+                    "invalid character {:?} at offset {}",
+                    character, offset
+                )
+            }
+            DecodeError::InvalidPadding { offset } => {
+                write!(formatter, "invalid padding at offset {}", offset)
+            }
+            DecodeError::InvalidLength { offset } => {
+                write!(formatter, "input truncated at offset {}", offset)
+            }
+            DecodeError::TrailingBits { offset } => {
+                write!(formatter, "non-zero trailing bits at offset {}", offset)
+            }
+        }
+    }
+}
+
+impl Error for DecodeError {}
+
+impl From<DecodeError> for std::io::Error {
+    fn from(error: DecodeError) -> std::io::Error {
+        std::io::Error::new(ErrorKind::InvalidData, error)
+    }
+}
+
+/// builds a table mapping each byte to its index in `alphabet`, or to `INVALID`.
+pub(crate) const fn decode_table(alphabet: &[u8], case_insensitive: bool) -> [u8; 256] {
+    let mut table = [INVALID; 256];
+    let mut i = 0;
+
+    while i < alphabet.len() {
+        table[alphabet[i] as usize] = i as u8;
+
+        if case_insensitive {
+            table[alphabet[i].to_ascii_lowercase() as usize] = i as u8;
+        }
+
+        i += 1;
+    }
+
+    table
+}
+
+/// generates the methods shared by every text encoding, given a method `engine(&self) -> Engine`.
+macro_rules! encoding_methods {
+    ($type: ident) => {
+        impl $type {
+            pub fn encode(&self, data: &[u8]) -> String {
+                self.engine().encode(data)
+            }
+
+            /// decodes `text`, ignoring whitespace.
+            pub fn decode(&self, text: &str) -> Result<Vec<u8>, crate::fmt::DecodeError> {
+                self.engine().decode(text)
+            }
+
+            /// returns a value which displays `data` in this encoding.
+            pub fn display<'a>(&self, data: &'a [u8]) -> crate::fmt::EncodedSlice<'a> {
+                crate::fmt::EncodedSlice::new(self.engine(), data)
+            }
+
+            /// returns a writer which encodes all data written through it into `inner`. see `EncodingWriter`.
+            pub fn writer<W: std::io::Write>(&self, inner: W) -> crate::fmt::EncodingWriter<W> {
+                crate::fmt::EncodingWriter::new(self.engine(), inner)
+            }
+
+            /// returns a reader which decodes all data read from `inner`. see `DecodingReader`.
+            pub fn reader<R: std::io::Read>(&self, inner: R) -> crate::fmt::DecodingReader<R> {
+                crate::fmt::DecodingReader::new(self.engine(), inner)
+            }
+        }
+    };
+}
+
+#[derive(Clone, Copy, Debug)]
+pub(crate) enum Engine {
+    Bits(Bits),
+    Number(Number),
+}
+
+/// an encoding which maps each group of `bits` bits to a character, such as base64 or base32.
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct Bits {
+    pub(crate) bits: u32,
+    pub(crate) alphabet: &'static [u8],
+    pub(crate) decode: &'static [u8; 256],
+    pub(crate) padding: bool,
+
+    // a character ignored when decoding, in addition to whitespace.
+    pub(crate) separator: Option<u8>,
+}
+
+/// an encoding which treats data as one big-endian number, written in base `alphabet.len()`, such as base58.
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct Number {
+    pub(crate) alphabet: &'static [u8],
+    pub(crate) decode: &'static [u8; 256],
+}
+
+impl Engine {
+    pub(crate) fn encode(&self, data: &[u8]) -> String {
+        let mut text = String::new();
+
+        self.encode_into(data, true, &mut text);
+        text
+    }
+
+    pub(crate) fn decode(&self, text: &str) -> Result<Vec<u8>, DecodeError> {
+        let mut state = DecodeState::new(*self);
+        let mut data = vec![];
+
+        for byte in text.bytes() {
+            state.push(byte, &mut data).map_err(|x| x.with_text(text))?;
+        }
+
+        state.finish(&mut data)?;
+
+        Ok(data)
+    }
+
+    // encodes `data`, which must be a whole number of groups unless `last` is set.
+    fn encode_into(&self, data: &[u8], last: bool, text: &mut String) {
+        match self {
+            Engine::Bits(bits) => bits.encode_into(data, last, text),
+            Engine::Number(number) => number.encode_into(data, text),
+        }
+    }
+
+    // returns the number of bytes which may be encoded independently, or `None` if the data must be encoded whole.
+    fn group(&self) -> Option<usize> {
+        match self {
+            Engine::Bits(bits) => Some(bits.group()),
+            Engine::Number(_) => None,
+        }
+    }
+}
+
+impl Bits {
+    // the number of bytes encoded by a whole number of characters.
+    fn group(&self) -> usize {
+        self.block() * self.bits as usize / 8
+    }
+
+    // the number of characters which encode a whole number of bytes, to which padding fills the output.
+    fn block(&self) -> usize {
+        8 / gcd(self.bits as usize, 8)
+    }
+
+    fn encode_into(&self, data: &[u8], last: bool, text: &mut String) {
+        let mask = (1 << self.bits) - 1;
+        let mut value = 0u32;
+        let mut available = 0;
+        let mut written = 0;
+
+        debug_assert!(last || data.len().is_multiple_of(self.group()));
+
+        for byte in data {
+            value = (value << 8) | *byte as u32;
+            available += 8;
+
+            while available >= self.bits {
+                available -= self.bits;
+                text.push(self.alphabet[((value >> available) & mask) as usize] as char);
+                written += 1;
+            }
+
+            value &= (1 << available) - 1;
+        }
+
+        if available > 0 {
+            text.push(self.alphabet[((value << (self.bits - available)) & mask) as usize] as char);
+            written += 1;
+        }
+
+        if last && self.padding {
+            while written % self.block() != 0 {
+                text.push('=');
+                written += 1;
+            }
+        }
+    }
+
+    // whether a final group of `count` characters encodes a whole number of bytes.
+    fn is_valid_remainder(&self, count: usize) -> bool {
+        (count * self.bits as usize) % 8 < self.bits as usize
+    }
+}
+
+impl Number {
+    fn base(&self) -> u32 {
+        self.alphabet.len() as u32
+    }
+
+    fn encode_into(&self, data: &[u8], text: &mut String) {
+        let zeros = data.iter().take_while(|x| **x == 0).count();
+
+        // the digits of the encoded number, least significant first.
+        let mut digits: Vec<u8> = vec![];
+
+        for byte in &data[zeros..] {
+            let mut carry = *byte as u32;
+
+            for digit in &mut digits {
+                carry += (*digit as u32) << 8;
+                *digit = (carry % self.base()) as u8;
+                carry /= self.base();
+            }
+
+            while carry > 0 {
+                digits.push((carry % self.base()) as u8);
+                carry /= self.base();
+            }
+        }
+
+        text.extend(std::iter::repeat_n(self.alphabet[0] as char, zeros));
+        text.extend(
+            digits
+                .iter()
+                .rev()
+                .map(|x| self.alphabet[*x as usize] as char),
+        );
+    }
+}
+
+fn gcd(a: usize, b: usize) -> usize {
+    match b {
+        0 => a,
+        b => gcd(b, a % b),
+    }
+}
+
+// the incremental state of a decoder, fed one byte at a time.
+enum DecodeState {
+    Bits {
+        encoding: Bits,
+        value: u32,
+        available: u32,
+        count: usize,
+        padding: usize,
+        last: usize,
+        offset: usize,
+    },
+    Number {
+        encoding: Number,
+        digits: Vec<u8>,
+        offset: usize,
+    },
+}
+
+impl DecodeState {
+    fn new(engine: Engine) -> DecodeState {
+        match engine {
+            Engine::Bits(encoding) => DecodeState::Bits {
+                encoding,
+                value: 0,
+                available: 0,
+                count: 0,
+                padding: 0,
+                last: 0,
+                offset: 0,
+            },
+            Engine::Number(encoding) => DecodeState::Number {
+                encoding,
+                digits: vec![],
+                offset: 0,
+            },
+        }
+    }
+
+    fn push(&mut self, byte: u8, data: &mut Vec<u8>) -> Result<(), DecodeError> {
+        let invalid = |offset| DecodeError::InvalidCharacter {
+            offset,
+            character: match byte.is_ascii() {
+                true => byte as char,
+                false => char::REPLACEMENT_CHARACTER,
+            },
+        };
+
+        match self {
+            DecodeState::Bits {
+                encoding,
+                value,
+                available,
+                count,
+                padding,
+                last,
+                offset,
+            } => {
+                let position = *offset;
+
+                *offset += 1;
+
+                if byte.is_ascii_whitespace() || Some(byte) == encoding.separator {
+                    return Ok(());
+                }
+
+                if byte == b'=' && encoding.padding {
+                    let remainder = *count % encoding.block();
+
+                    if remainder == 0
+                        || !encoding.is_valid_remainder(remainder)
+                        || remainder + *padding >= encoding.block()
+                    {
+                        return Err(DecodeError::InvalidPadding { offset: position });
+                    }
+
+                    *padding += 1;
+                    return Ok(());
+                }
+
+                if *padding > 0 {
+                    return Err(DecodeError::InvalidPadding { offset: position });
+                }
+
+                let digit = encoding.decode[byte as usize];
+
+                if digit == INVALID {
+                    return Err(invalid(position));
+                }
+
+                *value = (*value << encoding.bits) | digit as u32;
+                *available += encoding.bits;
+                *count += 1;
+                *last = position;
+
+                if *available >= 8 {
+                    *available -= 8;
+                    data.push((*value >> *available) as u8);
+                    *value &= (1 << *available) - 1;
+                }
+
+                Ok(())
+            }
+            DecodeState::Number {
+                encoding,
+                digits,
+                offset,
+            } => {
+                let position = *offset;
+
+                *offset += 1;
+
+                if byte.is_ascii_whitespace() {
+                    return Ok(());
+                }
+
+                match encoding.decode[byte as usize] {
+                    INVALID => Err(invalid(position)),
+                    digit => {
+                        digits.push(digit);
+                        Ok(())
+                    }
+                }
+            }
+        }
+    }
+
+    fn finish(&mut self, data: &mut Vec<u8>) -> Result<(), DecodeError> {
+        match self {
+            DecodeState::Bits {
+                encoding,
+                value,
+                count,
+                padding,
+                last,
+                offset,
+                ..
+            } => {
+                let remainder = *count % encoding.block();
+
+                if !encoding.is_valid_remainder(remainder) {
+                    return Err(DecodeError::InvalidLength { offset: *offset });
+                }
+
+                if *value != 0 {
+                    return Err(DecodeError::TrailingBits { offset: *last });
+                }
+
+                if encoding.padding && remainder > 0 && remainder + *padding != encoding.block() {
+                    return Err(DecodeError::InvalidPadding { offset: *offset });
+                }
+
+                Ok(())
+            }
+            DecodeState::Number {
+                encoding, digits, ..
+            } => {
+                let zeros = digits.iter().take_while(|x| **x == 0).count();
+
+                // the bytes of the decoded number, least significant first.
+                let mut bytes: Vec<u8> = vec![];
+
+                for digit in &digits[zeros..] {
+                    let mut carry = *digit as u32;
+
+                    for byte in &mut bytes {
+                        carry += *byte as u32 * encoding.base();
+                        *byte = carry as u8;
+                        carry >>= 8;
+                    }
+
+                    while carry > 0 {
+                        bytes.push(carry as u8);
+                        carry >>= 8;
+                    }
+                }
+
+                data.extend(std::iter::repeat_n(0, zeros));
+                data.extend(bytes.iter().rev());
+                digits.clear();
+
+                Ok(())
+            }
+        }
+    }
+}
+
+/// displays a byte slice as text in some encoding. see, for example, `Base64::display`.
+#[derive(Clone, Copy, Debug)]
+pub struct EncodedSlice<'a> {
+    engine: Engine,
+    data: &'a [u8],
+}
+
+impl<'a> EncodedSlice<'a> {
+    pub(crate) fn new(engine: Engine, data: &'a [u8]) -> EncodedSlice<'a> {
+        EncodedSlice { engine, data }
+    }
+}
+
+impl Display for EncodedSlice<'_> {
+    fn fmt(&self, formatter: &mut Formatter) -> Result<(), std::fmt::Error> {
+        formatter.write_str(&self.engine.encode(self.data))
+    }
+}
+
+/// a writer adapter which encodes all data written through it as text into an inner stream.
+///
+/// the final, partial group of data can only be encoded once all data has been written, so `finish` must be called to
+/// complete the output. if the writer is dropped instead, the output is completed and any error is ignored. encodings
+/// which treat data as a single number, such as base58, buffer everything until then.
+///
+/// # examples.
+///
+/// ```
+/// # use ari::fmt::Base64;
+/// # use std::io::Write;
+///
+/// let mut writer = Base64::STANDARD.writer(vec![]);
+///
+/// writer.write_all(b"hello, ").unwrap();
+/// writer.write_all(b"world.").unwrap();
+///
+/// assert_eq!(writer.finish().unwrap(), b"aGVsbG8sIHdvcmxkLg==");
+/// ```
+pub struct EncodingWriter<W>
+where
+    W: Write,
+{
+    inner: Option<W>,
+    engine: Engine,
+    pending: Vec<u8>,
+}
+
+impl<W> EncodingWriter<W>
+where
+    W: Write,
+{
+    pub(crate) fn new(engine: Engine, inner: W) -> EncodingWriter<W> {
+        EncodingWriter {
+            inner: Some(inner),
+            engine,
+            pending: vec![],
+        }
+    }
+
+    /// encodes any remaining data, returning the inner stream.
+    pub fn finish(mut self) -> Result<W, std::io::Error> {
+        self.complete()?;
+
+        Ok(self
+            .inner
+            .take()
+            .expect("the inner stream is only taken once"))
+    }
+
+    pub fn get_ref(&self) -> &W {
+        self.inner
+            .as_ref()
+            .expect("the inner stream is only taken once")
+    }
+
+    fn complete(&mut self) -> Result<(), std::io::Error> {
+        let mut text = String::new();
+
+        self.engine.encode_into(&self.pending, true, &mut text);
+        self.pending.clear();
+
+        let inner = self
+            .inner
+            .as_mut()
+            .expect("the inner stream is only taken once");
+
+        inner.write_all(text.as_bytes())?;
+        inner.flush()
+    }
+}
+
+impl<W> Write for EncodingWriter<W>
+where
+    W: Write,
+{
+    fn write(&mut self, buffer: &[u8]) -> Result<usize, std::io::Error> {
+        self.pending.extend_from_slice(buffer);
+
+        if let Some(group) = self.engine.group() {
+            let whole = self.pending.len() / group * group;
+            let mut text = String::with_capacity(whole * 2);
+
+            self.engine
+                .encode_into(&self.pending[..whole], false, &mut text);
+            self.pending.drain(..whole);
+
+            self.inner
+                .as_mut()
+                .expect("the inner stream is only taken once")
+                .write_all(text.as_bytes())?;
+        }
+
+        Ok(buffer.len())
+    }
+
+    /// flushes the inner stream. the final, partial group of data is not written until `finish`.
+    fn flush(&mut self) -> Result<(), std::io::Error> {
+        self.inner
+            .as_mut()
+            .expect("the inner stream is only taken once")
+            .flush()
+    }
+}
+
+impl<W> Drop for EncodingWriter<W>
+where
+    W: Write,
+{
+    fn drop(&mut self) {
+        if self.inner.is_some() {
+            let _ = self.complete();
+        }
+    }
+}
+
+/// a reader adapter which decodes text read from an inner stream. whitespace is ignored.
+///
+/// invalid input fails with `ErrorKind::InvalidData`, wrapping a `DecodeError` with the offset of the offending byte in
+/// the inner stream. data decoded before the offending byte is returned first, and once the error has been returned,
+/// every later read fails with it again. encodings which treat data as a single number, such as base58, read all input
+/// before producing any data.
+///
+/// # examples.
+///
+/// ```
+/// # use ari::fmt::{Base32, DecodeError};
+/// # use std::io::Read;
+///
+/// let mut data = vec![];
+///
+/// Base32::RFC4648.reader(&b"NBSWY3DP\nEE======"[..]).read_to_end(&mut data).unwrap();
+///
+/// assert_eq!(data, b"hello!");
+///
+/// let error = Base32::RFC4648.reader(&b"NBSWY1DP"[..]).read_to_end(&mut data).unwrap_err();
+/// let error = error.into_inner().unwrap().downcast::<DecodeError>().unwrap();
+///
+/// assert_eq!(error.offset(), 5);
+///
+/// let mut reader = Base32::RFC4648.reader(&b"NBSWY3D"[..]);
+///
+/// assert!(reader.read_to_end(&mut data).is_err());
+/// assert!(reader.read(&mut [0; 8]).is_err());
+/// ```
+pub struct DecodingReader<R>
+where
+    R: Read,
+{
+    inner: R,
+    state: DecodeState,
+    decoded: Vec<u8>,
+    consumed: usize,
+    finished: bool,
+
+    // the error which ended decoding, returned by every read once `decoded` is exhausted.
+    error: Option<DecodeError>,
+}
+
+impl<R> DecodingReader<R>
+where
+    R: Read,
+{
+    pub(crate) fn new(engine: Engine, inner: R) -> DecodingReader<R> {
+        DecodingReader {
+            inner,
+            state: DecodeState::new(engine),
+            decoded: vec![],
+            consumed: 0,
+            finished: false,
+            error: None,
+        }
+    }
+
+    pub fn get_ref(&self) -> &R {
+        &self.inner
+    }
+
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+}
+
+impl<R> Read for DecodingReader<R>
+where
+    R: Read,
+{
+    fn read(&mut self, buffer: &mut [u8]) -> Result<usize, std::io::Error> {
+        loop {
+            if self.consumed < self.decoded.len() {
+                let length = buffer.len().min(self.decoded.len() - self.consumed);
+
+                buffer[..length]
+                    .copy_from_slice(&self.decoded[self.consumed..self.consumed + length]);
+                self.consumed += length;
+
+                return Ok(length);
+            }
+
+            self.decoded.clear();
+            self.consumed = 0;
+
+            if let Some(error) = self.error {
+                return Err(error.into());
+            }
+
+            if self.finished || buffer.is_empty() {
+                return Ok(0);
+            }
+
+            let mut chunk = [0; READ_CHUNK];
+            let read = match self.inner.read(&mut chunk) {
+                Ok(read) => read,
+                Err(error) if error.kind() == ErrorKind::Interrupted => continue,
+                Err(error) => return Err(error),
+            };
+
+            if read == 0 {
+                self.finished = true;
+                self.error = self.state.finish(&mut self.decoded).err();
+            }
+
+            for byte in &chunk[..read] {
+                if let Err(error) = self.state.push(*byte, &mut self.decoded) {
+                    self.error = Some(error);
+                    break;
+                }
+            }
+        }
+    }
+}
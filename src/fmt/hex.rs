@@ -1,44 +1,313 @@
+use std::error::Error;
 use std::fmt::{Debug, Display, Formatter, LowerHex, UpperHex};
+use std::io::ErrorKind;
 
 pub fn to_hex(data: &[u8]) -> String {
     format!("{:x}", HexSlice(data))
 }
 
+/// decodes `string`, ignoring whitespace.
+///
+/// fails with `ErrorKind::InvalidInput`, wrapping a `HexError`. see `HexOptions` for other formats.
 pub fn from_hex(string: &str) -> Result<Vec<u8>, std::io::Error> {
-    let mut data = Vec::with_capacity(string.len() / 2);
-    let mut value = 0;
-    let mut processed = 0;
-
-    for byte in string.bytes() {
-        value <<= 4;
-
-        #[rustfmt::skip]
-        match byte {
-            b'A'..=b'F' => value |= byte - b'A' + 10,
-            b'a'..=b'f' => value |= byte - b'a' + 10,
-            b'0'..=b'9' => value |= byte - b'0',
-
-            b' '  => { value >>= 4; continue; },
-            b'\r' => { value >>= 4; continue; },
-            b'\n' => { value >>= 4; continue; },
-            b'\t' => { value >>= 4; continue; },
-            _ => return Err(std::io::ErrorKind::InvalidInput.into()),
+    HexOptions::new()
+        .with_whitespace(true)
+        .decode(string)
+        .map_err(|x| std::io::Error::new(ErrorKind::InvalidInput, x))
+}
+
+/// decodes `string` into `output`, which must be exactly as long as the decoded data. `output` is left partially
+/// written on failure.
+///
+/// # examples.
+///
+/// ```
+/// # use ari::fmt::HexError;
+///
+/// let mut output = [0; 4];
+///
+/// ari::fmt::decode_hex_into("deadbeef", &mut output).unwrap();
+///
+/// assert_eq!(output, [0xde, 0xad, 0xbe, 0xef]);
+/// assert_eq!(
+///     ari::fmt::decode_hex_into("deadbeefff", &mut output),
+///     Err(HexError::InvalidLength { expected: 4, actual: 5 })
+/// );
+/// ```
+pub fn decode_hex_into(string: &str, output: &mut [u8]) -> Result<(), HexError> {
+    HexOptions::new().decode_into(string, output)
+}
+
+/// decodes `string` into an array of exactly `N` bytes.
+///
+/// # examples.
+///
+/// ```
+/// # use ari::fmt::HexError;
+///
+/// let digest = ari::fmt::from_hex_array::<4>("0badf00d").unwrap();
+///
+/// assert_eq!(digest, [0x0b, 0xad, 0xf0, 0x0d]);
+/// assert_eq!(
+///     ari::fmt::from_hex_array::<4>("0badfood"),
+///     Err(HexError::InvalidCharacter { offset: 5, character: 'o' })
+/// );
+/// ```
+pub fn from_hex_array<const N: usize>(string: &str) -> Result<[u8; N], HexError> {
+    HexOptions::new().decode_array(string)
+}
+
+/// an error produced when decoding hex. offsets are in bytes from the start of the input.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum HexError {
+    /// a character which is not a hex digit, or a separator or prefix which is misplaced or not allowed.
+    InvalidCharacter { offset: usize, character: char },
+
+    /// the input ends, at this offset, halfway through a byte.
+    OddLength { offset: usize },
+
+    /// the input decodes to `actual` bytes, rather than the `expected` number.
+    InvalidLength { expected: usize, actual: usize },
+}
+
+impl Display for HexError {
+    fn fmt(&self, formatter: &mut Formatter) -> Result<(), std::fmt::Error> {
+        match self {
+            HexError::InvalidCharacter { offset, character } => {
+                write!(
+                    formatter,
+                    "invalid character {:?} at offset {}",
+                    character, offset
+                )
+            }
+            HexError::OddLength { offset } => {
+                write!(
+                    formatter,
+                    "odd number of hex digits, ending at offset {}",
+                    offset
+                )
+            }
+            HexError::InvalidLength { expected, actual } => {
+                write!(
+                    formatter,
+                    "expected {} bytes, but found {}",
+                    expected, actual
+                )
+            }
+        }
+    }
+}
+
+impl Error for HexError {}
+
+impl From<HexError> for std::io::Error {
+    fn from(error: HexError) -> std::io::Error {
+        std::io::Error::new(ErrorKind::InvalidInput, error)
+    }
+}
+
+/// the characters accepted, besides hex digits, when decoding hex.
+///
+/// # examples.
+///
+/// ```
+/// # use ari::fmt::{HexError, HexOptions};
+///
+/// let options = HexOptions::new().with_prefix(true).with_separators(true);
+///
+/// assert_eq!(options.decode("0xcafe").unwrap(), [0xca, 0xfe]);
+/// assert_eq!(options.decode_array::<6>("00:1a:2b:3c:4d:5e").unwrap(), [0, 0x1a, 0x2b, 0x3c, 0x4d, 0x5e]);
+/// assert_eq!(options.decode("ca-fe").unwrap(), [0xca, 0xfe]);
+///
+/// assert_eq!(
+///     options.decode("c:afe"),
+///     Err(HexError::InvalidCharacter { offset: 1, character: ':' })
+/// );
+/// assert_eq!(
+///     options.decode("00:11-22"),
+///     Err(HexError::InvalidCharacter { offset: 5, character: '-' })
+/// );
+/// assert_eq!(
+///     options.decode("0x"),
+///     Err(HexError::InvalidCharacter { offset: 1, character: 'x' })
+/// );
+///
+/// assert_eq!(options.with_whitespace(true).decode(" 0xcafe\n").unwrap(), [0xca, 0xfe]);
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct HexOptions {
+    prefix: bool,
+    separators: bool,
+    whitespace: bool,
+}
+
+impl HexOptions {
+    /// accepts hex digits only.
+    pub const fn new() -> HexOptions {
+        HexOptions {
+            prefix: false,
+            separators: false,
+            whitespace: false,
         }
+    }
+
+    /// sets whether a leading `0x` or `0X` is accepted. it must be followed by at least one byte.
+    pub const fn with_prefix(mut self, prefix: bool) -> HexOptions {
+        self.prefix = prefix;
+        self
+    }
+
+    /// sets whether a single `:` or `-` is accepted between bytes, as in `00:1a:2b` or `00-1a-2b`. every separator must
+    /// be the same as the first.
+    pub const fn with_separators(mut self, separators: bool) -> HexOptions {
+        self.separators = separators;
+        self
+    }
+
+    /// sets whether whitespace is accepted, and ignored, anywhere.
+    pub const fn with_whitespace(mut self, whitespace: bool) -> HexOptions {
+        self.whitespace = whitespace;
+        self
+    }
+
+    pub fn decode(&self, string: &str) -> Result<Vec<u8>, HexError> {
+        let mut data = Vec::with_capacity(string.len() / 2);
+
+        self.decode_with(string, |byte| data.push(byte))?;
 
-        processed += 1;
+        Ok(data)
+    }
+
+    /// decodes `string` into `output`, which must be exactly as long as the decoded data. see `decode_hex_into`.
+    pub fn decode_into(&self, string: &str, output: &mut [u8]) -> Result<(), HexError> {
+        let mut length = 0;
+
+        self.decode_with(string, |byte| {
+            if let Some(x) = output.get_mut(length) {
+                *x = byte;
+            }
+
+            length += 1;
+        })?;
 
-        if processed == 2 {
-            data.push(value);
-            processed = 0;
+        match length == output.len() {
+            true => Ok(()),
+            false => Err(HexError::InvalidLength {
+                expected: output.len(),
+                actual: length,
+            }),
         }
     }
 
-    match processed {
-        0 => Ok(data.into_iter().collect()),
-        _ => Err(std::io::ErrorKind::InvalidInput.into()),
+    /// decodes `string` into an array of exactly `N` bytes. see `from_hex_array`.
+    pub fn decode_array<const N: usize>(&self, string: &str) -> Result<[u8; N], HexError> {
+        let mut output = [0; N];
+
+        self.decode_into(string, &mut output)?;
+
+        Ok(output)
+    }
+
+    fn decode_with(&self, string: &str, mut push: impl FnMut(u8)) -> Result<(), HexError> {
+        let bytes = string.as_bytes();
+        let invalid = |offset: usize| HexError::InvalidCharacter {
+            offset,
+            character: string[offset..]
+                .chars()
+                .next()
+                .unwrap_or(char::REPLACEMENT_CHARACTER),
+        };
+
+        // the prefix may follow leading whitespace.
+        let leading = match self.whitespace {
+            true => bytes.iter().take_while(|x| x.is_ascii_whitespace()).count(),
+            false => 0,
+        };
+
+        let prefixed = self.prefix
+            && (bytes[leading..].starts_with(b"0x") || bytes[leading..].starts_with(b"0X"));
+        let start = match prefixed {
+            true => leading + 2,
+            false => leading,
+        };
+
+        // the high nibble of a partially decoded byte.
+        let mut high = None;
+
+        // the offset of a separator which has not yet been followed by a byte.
+        let mut separator = None;
+
+        // the first separator used, which every later separator must match.
+        let mut kind = None;
+        let mut written = false;
+
+        for (offset, byte) in bytes.iter().enumerate().skip(start) {
+            let digit = HEX_DIGITS[*byte as usize];
+
+            if digit != INVALID_DIGIT {
+                match high.take() {
+                    None => high = Some(digit),
+                    Some(high) => {
+                        push(high << 4 | digit);
+                        separator = None;
+                        written = true;
+                    }
+                }
+            } else if self.whitespace && byte.is_ascii_whitespace() {
+                continue;
+            } else if self.separators
+                && matches!(byte, b':' | b'-')
+                && *kind.get_or_insert(*byte) == *byte
+                && high.is_none()
+                && separator.is_none()
+                && written
+            {
+                separator = Some(offset);
+            } else {
+                return Err(invalid(offset));
+            }
+        }
+
+        if high.is_some() {
+            return Err(HexError::OddLength {
+                offset: string.len(),
+            });
+        }
+
+        if prefixed && !written {
+            return Err(invalid(leading + 1));
+        }
+
+        match separator {
+            Some(offset) => Err(invalid(offset)),
+            None => Ok(()),
+        }
     }
 }
 
+const INVALID_DIGIT: u8 = 0xff;
+
+// maps each byte to the value of the hex digit it represents, or to `INVALID_DIGIT`.
+const HEX_DIGITS: [u8; 256] = {
+    let mut table = [INVALID_DIGIT; 256];
+    let mut i = 0;
+
+    while i < 10 {
+        table[b'0' as usize + i] = i as u8;
+        i += 1;
+    }
+
+    i = 0;
+
+    while i < 6 {
+        table[b'a' as usize + i] = 10 + i as u8;
+        table[b'A' as usize + i] = 10 + i as u8;
+        i += 1;
+    }
+
+    table
+};
+
 pub struct HexSlice<'a>(pub &'a [u8]);
 
 impl Debug for HexSlice<'_> {
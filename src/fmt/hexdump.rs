@@ -0,0 +1,401 @@
+use std::convert::TryFrom;
+use std::fmt::Write as _;
+use std::fmt::{Display, Formatter};
+use std::io::{ErrorKind, Read, Write};
+
+/// the largest gap between consecutive offsets that `parse_hex_dump` will fill, so that a corrupt offset cannot exhaust
+/// memory.
+const MAX_GAP: usize = 1 << 26;
+
+/// the layout of a hex dump: an offset, hex columns in groups, and an ascii gutter on each line. the defaults match
+/// `xxd`.
+///
+/// # examples.
+///
+/// ```
+/// # use ari::fmt::HexDumpFormat;
+///
+/// let format = HexDumpFormat::new()
+///     .with_bytes_per_line(8)
+///     .with_group_size(4)
+///     .with_offset(0x1000)
+///     .with_uppercase(true);
+///
+/// assert_eq!(
+///     format.display(b"hello, world.\n").to_string(),
+///     "00001000: 68656C6C 6F2C2077  hello, w\n\
+///      00001008: 6F726C64 2E0A      orld..\n"
+/// );
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct HexDumpFormat {
+    bytes_per_line: usize,
+    group_size: usize,
+    offset: u64,
+    uppercase: bool,
+    collapse: bool,
+}
+
+impl HexDumpFormat {
+    /// 16 bytes per line in groups of 2, with lowercase hex and offsets starting at zero.
+    pub fn new() -> HexDumpFormat {
+        HexDumpFormat {
+            bytes_per_line: 16,
+            group_size: 2,
+            offset: 0,
+            uppercase: false,
+            collapse: false,
+        }
+    }
+
+    /// sets the number of bytes on each line. must be greater than zero.
+    pub fn with_bytes_per_line(mut self, bytes: usize) -> HexDumpFormat {
+        assert!(bytes > 0, "a hex dump must have at least one byte per line");
+
+        self.bytes_per_line = bytes;
+        self
+    }
+
+    /// sets the number of bytes between the spaces in the hex columns, or zero for no spaces.
+    pub fn with_group_size(mut self, bytes: usize) -> HexDumpFormat {
+        self.group_size = bytes;
+        self
+    }
+
+    /// sets the offset label of the first byte.
+    pub fn with_offset(mut self, offset: u64) -> HexDumpFormat {
+        self.offset = offset;
+        self
+    }
+
+    pub fn with_uppercase(mut self, uppercase: bool) -> HexDumpFormat {
+        self.uppercase = uppercase;
+        self
+    }
+
+    /// sets whether lines identical to the line before them are collapsed into a single `*`. the last line is always
+    /// written, so that the length of the data is still known.
+    pub fn with_collapse(mut self, collapse: bool) -> HexDumpFormat {
+        self.collapse = collapse;
+        self
+    }
+
+    /// returns a value which displays `data` in this format.
+    pub fn display<'a>(&self, data: &'a [u8]) -> HexDump<'a> {
+        HexDump {
+            data,
+            format: *self,
+        }
+    }
+
+    /// dumps everything read from `reader` into `writer`, one line at a time, returning the number of bytes dumped.
+    ///
+    /// # examples.
+    ///
+    /// ```
+    /// # use ari::fmt::HexDumpFormat;
+    ///
+    /// let mut output = vec![];
+    /// let format = HexDumpFormat::new().with_collapse(true);
+    ///
+    /// format.dump(&[0u8; 64][..], &mut output).unwrap();
+    ///
+    /// assert_eq!(
+    ///     String::from_utf8(output).unwrap(),
+    ///     "00000000: 0000 0000 0000 0000 0000 0000 0000 0000  ................\n\
+    ///      *\n\
+    ///      00000030: 0000 0000 0000 0000 0000 0000 0000 0000  ................\n"
+    /// );
+    /// ```
+    pub fn dump(
+        &self,
+        mut reader: impl Read,
+        mut writer: impl Write,
+    ) -> Result<u64, std::io::Error> {
+        let mut dumper = Dumper::new(*self);
+        let mut line = vec![0; self.bytes_per_line];
+        let mut total = 0;
+        let mut text = String::new();
+
+        loop {
+            let mut filled = 0;
+
+            while filled < line.len() {
+                match reader.read(&mut line[filled..]) {
+                    Ok(0) => break,
+                    Ok(read) => filled += read,
+                    Err(error) if error.kind() == ErrorKind::Interrupted => {}
+                    Err(error) => return Err(error),
+                }
+            }
+
+            if filled > 0 {
+                dumper.line(&line[..filled], &mut text);
+                total += filled as u64;
+            }
+
+            if filled < line.len() {
+                break;
+            }
+
+            writer.write_all(text.as_bytes())?;
+            text.clear();
+        }
+
+        dumper.finish(&mut text);
+        writer.write_all(text.as_bytes())?;
+        writer.flush()?;
+
+        Ok(total)
+    }
+}
+
+impl Default for HexDumpFormat {
+    fn default() -> HexDumpFormat {
+        HexDumpFormat::new()
+    }
+}
+
+/// displays bytes as a hex dump, in the style of `xxd`. see `HexDumpFormat`.
+///
+/// # examples.
+///
+/// ```
+/// # use ari::fmt::HexDump;
+///
+/// assert_eq!(
+///     HexDump::new(b"hello, world.\n\x00\xff").to_string(),
+///     "00000000: 6865 6c6c 6f2c 2077 6f72 6c64 2e0a 00ff  hello, world....\n"
+/// );
+/// ```
+#[derive(Clone, Copy, Debug)]
+pub struct HexDump<'a> {
+    data: &'a [u8],
+    format: HexDumpFormat,
+}
+
+impl<'a> HexDump<'a> {
+    pub fn new(data: &'a [u8]) -> HexDump<'a> {
+        HexDumpFormat::new().display(data)
+    }
+}
+
+impl Display for HexDump<'_> {
+    fn fmt(&self, formatter: &mut Formatter) -> Result<(), std::fmt::Error> {
+        let mut dumper = Dumper::new(self.format);
+        let mut text = String::new();
+
+        for line in self.data.chunks(self.format.bytes_per_line) {
+            dumper.line(line, &mut text);
+            formatter.write_str(&text)?;
+            text.clear();
+        }
+
+        dumper.finish(&mut text);
+        formatter.write_str(&text)
+    }
+}
+
+// formats consecutive lines of a dump, collapsing repeated lines.
+struct Dumper {
+    format: HexDumpFormat,
+    offset: u64,
+    previous: Vec<u8>,
+
+    // the offset of the most recent line collapsed into a `*`, if the line after it has not yet been written.
+    collapsed: Option<u64>,
+}
+
+impl Dumper {
+    fn new(format: HexDumpFormat) -> Dumper {
+        Dumper {
+            format,
+            offset: format.offset,
+            previous: vec![],
+            collapsed: None,
+        }
+    }
+
+    fn line(&mut self, line: &[u8], text: &mut String) {
+        let offset = self.offset;
+
+        self.offset += line.len() as u64;
+
+        if self.format.collapse && line.len() == self.format.bytes_per_line && line == self.previous
+        {
+            if self.collapsed.is_none() {
+                text.push_str("*\n");
+            }
+
+            self.collapsed = Some(offset);
+            return;
+        }
+
+        self.collapsed = None;
+        self.previous.clear();
+        self.previous.extend_from_slice(line);
+        self.write(offset, line, text);
+    }
+
+    fn finish(&mut self, text: &mut String) {
+        if let Some(offset) = self.collapsed.take() {
+            let line = std::mem::take(&mut self.previous);
+
+            self.write(offset, &line, text);
+        }
+    }
+
+    fn write(&self, offset: u64, line: &[u8], text: &mut String) {
+        let format = &self.format;
+        let mut hex = String::new();
+
+        for (i, byte) in line.iter().enumerate() {
+            if i > 0 && format.group_size > 0 && i % format.group_size == 0 {
+                hex.push(' ');
+            }
+
+            let _ = match format.uppercase {
+                true => write!(hex, "{:02X}", byte),
+                false => write!(hex, "{:02x}", byte),
+            };
+        }
+
+        let width = format.bytes_per_line * 2
+            + match format.group_size {
+                0 => 0,
+                size => (format.bytes_per_line - 1) / size,
+            };
+
+        let ascii = line
+            .iter()
+            .map(|x| match x {
+                0x20..=0x7e => *x as char,
+                _ => '.',
+            })
+            .collect::<String>();
+
+        let _ = match format.uppercase {
+            true => writeln!(
+                text,
+                "{:08X}: {:width$}  {}",
+                offset,
+                hex,
+                ascii,
+                width = width
+            ),
+            false => writeln!(
+                text,
+                "{:08x}: {:width$}  {}",
+                offset,
+                hex,
+                ascii,
+                width = width
+            ),
+        };
+    }
+}
+
+/// parses a hex dump in the format written by `xxd` and `HexDump`, returning the bytes it describes.
+///
+/// the returned data starts at the offset of the first line. gaps between offsets are filled with zeros, and a `*` line
+/// repeats the line before it up to the next offset, so it must be followed by another line. text after two consecutive
+/// spaces on each line, the ascii gutter, is ignored.
+///
+/// an offset more than 64 MiB past the end of the line before it is rejected as invalid.
+///
+/// # examples.
+///
+/// ```
+/// # use ari::fmt::{parse_hex_dump, HexDumpFormat};
+///
+/// let data = [[0u8; 16], [7; 16], [7; 16], [7; 16], [1; 16]].concat();
+/// let dump = HexDumpFormat::new().with_collapse(true).display(&data).to_string();
+///
+/// assert_eq!(dump.lines().nth(2), Some("*"));
+/// assert_eq!(parse_hex_dump(&dump).unwrap(), data);
+///
+/// assert_eq!(parse_hex_dump("00000000: 6869 0a  hi.").unwrap(), b"hi\n");
+/// assert!(parse_hex_dump("00000000: 6g69  hi").is_err());
+/// assert!(parse_hex_dump("0: 00\n7fffffffffff: 00").is_err());
+/// assert!(parse_hex_dump("00000000: 0000  ..\n*\n").is_err());
+///
+/// let data = (0..37u8).collect::<Vec<_>>();
+/// let dump = HexDumpFormat::new().with_group_size(0).display(&data).to_string();
+///
+/// assert_eq!(parse_hex_dump(&dump).unwrap(), data);
+/// ```
+pub fn parse_hex_dump(text: &str) -> Result<Vec<u8>, std::io::Error> {
+    let invalid = |line: usize, reason: &str| {
+        std::io::Error::new(
+            ErrorKind::InvalidData,
+            format!("line {}: {}", line + 1, reason),
+        )
+    };
+
+    let mut data: Vec<u8> = vec![];
+    let mut start = None;
+    let mut previous = 0;
+    let mut repeat = None;
+
+    for (number, line) in text.lines().enumerate() {
+        let line = line.trim_end_matches('\r');
+
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        if line.trim() == "*" {
+            repeat = Some(number);
+            continue;
+        }
+
+        let (offset, rest) = line
+            .split_once(':')
+            .ok_or_else(|| invalid(number, "missing offset"))?;
+
+        let offset = u64::from_str_radix(offset.trim(), 16)
+            .map_err(|_| invalid(number, "invalid offset"))?;
+        let start = *start.get_or_insert(offset);
+        let position = offset
+            .checked_sub(start)
+            .and_then(|x| usize::try_from(x).ok())
+            .filter(|x| *x >= data.len())
+            .ok_or_else(|| invalid(number, "offsets must increase"))?;
+
+        if position - data.len() > MAX_GAP {
+            return Err(invalid(number, "offset is too far past the previous line"));
+        }
+
+        // the hex columns never contain two consecutive spaces, whatever the group size, and are always separated from
+        // the gutter by at least two, since short lines are padded to the full width.
+        let hex = rest.trim_start_matches(' ');
+        let hex = hex.split("  ").next().unwrap_or("");
+        let bytes = crate::fmt::from_hex(hex).map_err(|_| invalid(number, "invalid hex"))?;
+
+        data.try_reserve(position - data.len() + bytes.len())
+            .map_err(|_| invalid(number, "out of memory"))?;
+
+        // the previous line is the last `previous` bytes of `data`.
+        if repeat.is_some() && previous > 0 {
+            let from = data.len() - previous;
+
+            while data.len() < position {
+                let length = previous.min(position - data.len());
+
+                data.extend_from_within(from..from + length);
+            }
+        }
+
+        data.resize(position, 0);
+        data.extend_from_slice(&bytes);
+        previous = bytes.len();
+        repeat = None;
+    }
+
+    // a `*` always precedes the line which ends the repeated run, so without one the dump is truncated.
+    if let Some(number) = repeat {
+        return Err(invalid(number, "`*` must be followed by a line"));
+    }
+
+    Ok(data)
+}
@@ -1,4 +1,5 @@
 use std::fmt::{Display, Formatter};
+use std::str::FromStr;
 use std::time::Duration;
 
 pub struct FormattedDuration(pub Duration);
@@ -60,6 +61,27 @@ impl Display for HumanDuration {
     }
 }
 
+/// parses a duration, as described by `parse_duration`.
+///
+/// # examples.
+///
+/// ```
+/// # use ari::fmt::HumanDuration;
+///
+/// let duration = "2 hours".parse::<HumanDuration>().unwrap();
+///
+/// assert_eq!(format!("{}", duration), "2 hours");
+/// assert_eq!(format!("{:#}", duration), "2h");
+/// assert_eq!("2h".parse::<HumanDuration>().unwrap().0, duration.0);
+/// ```
+impl FromStr for HumanDuration {
+    type Err = crate::fmt::ParseHumanError;
+
+    fn from_str(text: &str) -> Result<HumanDuration, Self::Err> {
+        crate::fmt::parse_duration(text).map(HumanDuration)
+    }
+}
+
 #[derive(Clone, Copy, Debug)]
 pub struct HumanBytes(pub u64);
 
@@ -71,6 +93,26 @@ impl Display for HumanBytes {
     }
 }
 
+/// parses a number of bytes, as described by `parse_bytes`.
+///
+/// # examples.
+///
+/// ```
+/// # use ari::fmt::HumanBytes;
+///
+/// let size = "1.50 MiB".parse::<HumanBytes>().unwrap();
+///
+/// assert_eq!(size.0, 1_572_864);
+/// assert_eq!(size.to_string(), "1.50 MiB");
+/// ```
+impl FromStr for HumanBytes {
+    type Err = crate::fmt::ParseHumanError;
+
+    fn from_str(text: &str) -> Result<HumanBytes, Self::Err> {
+        crate::fmt::parse_bytes(text).map(HumanBytes)
+    }
+}
+
 #[derive(Clone, Copy, Debug)]
 pub struct HumanDetailedBytes(pub u64, pub usize);
 
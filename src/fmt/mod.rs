@@ -1,5 +1,19 @@
+#[macro_use]
+mod encoding;
+
+mod base32;
+mod base58;
+mod base64;
 mod hex;
+mod hexdump;
 mod human;
+mod parse;
 
+pub use self::base32::*;
+pub use self::base58::*;
+pub use self::base64::*;
+pub use self::encoding::*;
 pub use self::hex::*;
+pub use self::hexdump::*;
 pub use self::human::*;
+pub use self::parse::*;
@@ -0,0 +1,282 @@
+use std::convert::TryFrom;
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+use std::io::ErrorKind;
+use std::time::Duration;
+
+/// the maximum number of fractional digits considered. later digits are ignored.
+const MAX_FRACTION_DIGITS: u32 = 18;
+
+const NANOSECONDS_PER_SECOND: u128 = 1_000_000_000;
+
+/// an error produced when parsing a human-readable size or duration. offsets are in bytes from the start of the input.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ParseHumanError {
+    /// the input is empty, or only whitespace.
+    Empty,
+
+    /// a number was expected at this offset.
+    InvalidNumber { offset: usize },
+
+    /// the unit at this offset is missing or unknown.
+    InvalidUnit { offset: usize },
+
+    /// the value is too large to be represented.
+    Overflow,
+}
+
+impl Display for ParseHumanError {
+    fn fmt(&self, formatter: &mut Formatter) -> Result<(), std::fmt::Error> {
+        match self {
+            ParseHumanError::Empty => write!(formatter, "empty input"),
+            ParseHumanError::InvalidNumber { offset } => {
+                write!(formatter, "expected a number at offset {}", offset)
+            }
+            ParseHumanError::InvalidUnit { offset } => {
+                write!(formatter, "invalid unit at offset {}", offset)
+            }
+            ParseHumanError::Overflow => write!(formatter, "value is too large"),
+        }
+    }
+}
+
+impl Error for ParseHumanError {}
+
+impl From<ParseHumanError> for std::io::Error {
+    fn from(error: ParseHumanError) -> std::io::Error {
+        std::io::Error::new(ErrorKind::InvalidInput, error)
+    }
+}
+
+/// parses a number of bytes, such as `1.5 GiB`, `200MB` or `4k`, as written by `HumanBytes`. units are
+/// case-insensitive.
+///
+/// - `B`, or no unit, is a single byte.
+/// - `KiB`, `MiB`, `GiB`, `TiB`, `PiB` and `EiB` are powers of 1024.
+/// - `KB`, `MB`, `GB`, `TB`, `PB` and `EB` are powers of 1000.
+/// - the single letters `k`, `m`, `g`, `t`, `p` and `e` are powers of 1024, as in `dd`.
+///
+/// fractional values are rounded to the nearest byte.
+///
+/// # examples.
+///
+/// ```
+/// # use ari::fmt::{parse_bytes, ParseHumanError};
+///
+/// assert_eq!(parse_bytes("1.5 GiB"), Ok(1_610_612_736));
+/// assert_eq!(parse_bytes("200MB"), Ok(200_000_000));
+/// assert_eq!(parse_bytes("4k"), Ok(4096));
+/// assert_eq!(parse_bytes("512"), Ok(512));
+/// assert_eq!(parse_bytes("12 parsecs"), Err(ParseHumanError::InvalidUnit { offset: 3 }));
+/// assert_eq!(parse_bytes("16 EiB"), Err(ParseHumanError::Overflow));
+/// ```
+pub fn parse_bytes(text: &str) -> Result<u64, ParseHumanError> {
+    let mut scanner = Scanner::new(text);
+
+    scanner.skip_whitespace();
+
+    if scanner.is_done() {
+        return Err(ParseHumanError::Empty);
+    }
+
+    let number = scanner.number()?;
+
+    scanner.skip_whitespace();
+
+    let (offset, unit) = scanner.unit();
+    let multiplier: u128 = match unit.to_ascii_lowercase().as_str() {
+        "" | "b" | "byte" | "bytes" => 1,
+        "k" | "kib" => 1 << 10,
+        "m" | "mib" => 1 << 20,
+        "g" | "gib" => 1 << 30,
+        "t" | "tib" => 1 << 40,
+        "p" | "pib" => 1 << 50,
+        "e" | "eib" => 1 << 60,
+        "kb" => 1_000,
+        "mb" => 1_000_000,
+        "gb" => 1_000_000_000,
+        "tb" => 1_000_000_000_000,
+        "pb" => 1_000_000_000_000_000,
+        "eb" => 1_000_000_000_000_000_000,
+        _ => return Err(ParseHumanError::InvalidUnit { offset }),
+    };
+
+    scanner.skip_whitespace();
+
+    if !scanner.is_done() {
+        return Err(ParseHumanError::InvalidUnit {
+            offset: scanner.position,
+        });
+    }
+
+    number
+        .times(multiplier)
+        .and_then(|x| u64::try_from(x).ok())
+        .ok_or(ParseHumanError::Overflow)
+}
+
+/// parses a duration made of one or more components, such as `1h 30m`, `2d`, `250ms` or `1w2d`, as written by
+/// `HumanDuration`. units are case-insensitive.
+///
+/// the units are `ns`, `us` (or `µs`), `ms`, `s`, `m` (or `min`), `h`, `d`, `w` and `y`, or their names in full, such as
+/// `seconds`. a year is 365 days.
+///
+/// # examples.
+///
+/// ```
+/// # use ari::fmt::{parse_duration, ParseHumanError};
+/// # use std::time::Duration;
+///
+/// assert_eq!(parse_duration("1h 30m"), Ok(Duration::from_secs(5400)));
+/// assert_eq!(parse_duration("1w2d"), Ok(Duration::from_secs(9 * 24 * 60 * 60)));
+/// assert_eq!(parse_duration("250ms"), Ok(Duration::from_millis(250)));
+/// assert_eq!(parse_duration("1.5 seconds"), Ok(Duration::from_millis(1500)));
+/// assert_eq!(parse_duration("3 fortnights"), Err(ParseHumanError::InvalidUnit { offset: 2 }));
+/// assert_eq!(parse_duration("10"), Err(ParseHumanError::InvalidUnit { offset: 2 }));
+/// ```
+pub fn parse_duration(text: &str) -> Result<Duration, ParseHumanError> {
+    let mut scanner = Scanner::new(text);
+    let mut total: u128 = 0;
+
+    scanner.skip_whitespace();
+
+    if scanner.is_done() {
+        return Err(ParseHumanError::Empty);
+    }
+
+    while !scanner.is_done() {
+        let number = scanner.number()?;
+
+        scanner.skip_whitespace();
+
+        let (offset, unit) = scanner.unit();
+        let nanoseconds: u128 = match unit.to_lowercase().as_str() {
+            "ns" | "nanosecond" | "nanoseconds" => 1,
+            "us" | "µs" | "μs" | "microsecond" | "microseconds" => 1_000,
+            "ms" | "millisecond" | "milliseconds" => 1_000_000,
+            "s" | "sec" | "secs" | "second" | "seconds" => NANOSECONDS_PER_SECOND,
+            "m" | "min" | "mins" | "minute" | "minutes" => 60 * NANOSECONDS_PER_SECOND,
+            "h" | "hr" | "hrs" | "hour" | "hours" => 60 * 60 * NANOSECONDS_PER_SECOND,
+            "d" | "day" | "days" => 24 * 60 * 60 * NANOSECONDS_PER_SECOND,
+            "w" | "week" | "weeks" => 7 * 24 * 60 * 60 * NANOSECONDS_PER_SECOND,
+            "y" | "year" | "years" => 365 * 24 * 60 * 60 * NANOSECONDS_PER_SECOND,
+            _ => return Err(ParseHumanError::InvalidUnit { offset }),
+        };
+
+        total = number
+            .times(nanoseconds)
+            .and_then(|x| total.checked_add(x))
+            .ok_or(ParseHumanError::Overflow)?;
+
+        scanner.skip_whitespace();
+    }
+
+    let seconds =
+        u64::try_from(total / NANOSECONDS_PER_SECOND).map_err(|_| ParseHumanError::Overflow)?;
+
+    Ok(Duration::new(
+        seconds,
+        (total % NANOSECONDS_PER_SECOND) as u32,
+    ))
+}
+
+// a decimal number, `integer + fraction / scale`.
+struct Decimal {
+    integer: u128,
+    fraction: u128,
+    scale: u128,
+}
+
+impl Decimal {
+    // multiplies this number by `multiplier`, rounding to the nearest integer.
+    fn times(&self, multiplier: u128) -> Option<u128> {
+        let fraction = self.fraction.checked_mul(multiplier)?;
+
+        self.integer
+            .checked_mul(multiplier)?
+            .checked_add((fraction + self.scale / 2) / self.scale)
+    }
+}
+
+struct Scanner<'a> {
+    text: &'a str,
+    position: usize,
+}
+
+impl<'a> Scanner<'a> {
+    fn new(text: &'a str) -> Scanner<'a> {
+        Scanner { text, position: 0 }
+    }
+
+    fn is_done(&self) -> bool {
+        self.position == self.text.len()
+    }
+
+    fn rest(&self) -> &'a str {
+        &self.text[self.position..]
+    }
+
+    fn skip_whitespace(&mut self) {
+        let rest = self.rest();
+
+        self.position += rest.len() - rest.trim_start().len();
+    }
+
+    // reads digits, optionally followed by a `.` and more digits.
+    fn number(&mut self) -> Result<Decimal, ParseHumanError> {
+        let start = self.position;
+        let mut number = Decimal {
+            integer: 0,
+            fraction: 0,
+            scale: 1,
+        };
+
+        let mut digits = 0;
+        let mut fractional = false;
+        let mut fraction_digits = 0;
+
+        for byte in self.rest().bytes() {
+            match byte {
+                b'0'..=b'9' if fractional => {
+                    digits += 1;
+
+                    if fraction_digits < MAX_FRACTION_DIGITS {
+                        number.fraction = number.fraction * 10 + (byte - b'0') as u128;
+                        number.scale *= 10;
+                        fraction_digits += 1;
+                    }
+                }
+                b'0'..=b'9' => {
+                    digits += 1;
+                    number.integer = number
+                        .integer
+                        .checked_mul(10)
+                        .and_then(|x| x.checked_add((byte - b'0') as u128))
+                        .ok_or(ParseHumanError::Overflow)?;
+                }
+                b'.' if !fractional => fractional = true,
+                _ => break,
+            }
+
+            self.position += 1;
+        }
+
+        match digits {
+            0 => Err(ParseHumanError::InvalidNumber { offset: start }),
+            _ => Ok(number),
+        }
+    }
+
+    // reads a run of letters, returning its offset.
+    fn unit(&mut self) -> (usize, &'a str) {
+        let start = self.position;
+        let length = self
+            .rest()
+            .find(|x: char| !x.is_alphabetic())
+            .unwrap_or(self.rest().len());
+
+        self.position += length;
+
+        (start, &self.text[start..start + length])
+    }
+}
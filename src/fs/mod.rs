@@ -1,7 +1,9 @@
 mod enumerate;
+mod split;
 mod sys;
 
 pub use self::enumerate::*;
+pub use self::split::*;
 
 use crate::io::ReadExt;
 use std::fs::File;
@@ -0,0 +1,346 @@
+use std::error::Error;
+use std::ffi::OsStr;
+use std::fmt::{Display, Formatter};
+use std::fs::File;
+use std::io::{ErrorKind, Read, Write};
+use std::path::{Path, PathBuf};
+
+use crate::crypto::HashAlgorithm;
+use crate::io::{HashingReader, HashingWriter};
+
+/// the version written to, and expected in, split manifests.
+const MANIFEST_VERSION: &str = "1";
+
+/// an error produced when joining the parts of a split file.
+#[derive(Debug)]
+pub enum JoinError {
+    Io(std::io::Error),
+
+    /// the manifest could not be parsed.
+    InvalidManifest(String),
+
+    /// the part at this zero-based index does not exist.
+    MissingPart {
+        index: usize,
+        path: PathBuf,
+    },
+
+    /// the part at this zero-based index does not match the size or hash recorded in the manifest.
+    CorruptPart {
+        index: usize,
+        path: PathBuf,
+    },
+
+    /// every part is intact, but the joined file does not match the hash recorded in the manifest.
+    CorruptFile,
+}
+
+impl Display for JoinError {
+    fn fmt(&self, formatter: &mut Formatter) -> Result<(), std::fmt::Error> {
+        match self {
+            JoinError::Io(error) => write!(formatter, "{}", error),
+            JoinError::InvalidManifest(reason) => write!(formatter, "invalid manifest: {}", reason),
+            JoinError::MissingPart { index, path } => {
+                write!(
+                    formatter,
+                    "part {} (`{}`) is missing",
+                    index,
+                    path.display()
+                )
+            }
+            JoinError::CorruptPart { index, path } => {
+                write!(
+                    formatter,
+                    "part {} (`{}`) is corrupt",
+                    index,
+                    path.display()
+                )
+            }
+            JoinError::CorruptFile => write!(formatter, "the joined file does not match its hash"),
+        }
+    }
+}
+
+impl Error for JoinError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            JoinError::Io(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+impl From<std::io::Error> for JoinError {
+    fn from(error: std::io::Error) -> JoinError {
+        JoinError::Io(error)
+    }
+}
+
+impl From<JoinError> for std::io::Error {
+    fn from(error: JoinError) -> std::io::Error {
+        match error {
+            JoinError::Io(error) => error,
+            JoinError::MissingPart { .. } => std::io::Error::new(ErrorKind::NotFound, error),
+            error => std::io::Error::new(ErrorKind::InvalidData, error),
+        }
+    }
+}
+
+/// splits the file at `path` into parts of at most `part_size` bytes, returning the path of the manifest.
+///
+/// parts are written next to the file, named after it with a numeric extension starting at `.001`. the manifest is
+/// named after the file with a `.manifest` extension, and records the sha-256 hash of every part and of the whole file.
+///
+/// # examples.
+///
+/// ```
+/// # use ari::fs::{self, JoinError};
+///
+/// let directory = std::env::temp_dir().join("ari-split-example");
+/// let path = directory.join("artifact.bin");
+/// let data = (0..2500u32).map(|x| x as u8).collect::<Vec<_>>();
+///
+/// std::fs::create_dir_all(&directory).unwrap();
+/// fs::write_all_bytes(&path, &data).unwrap();
+///
+/// let manifest = fs::split_file(&path, 1000).unwrap();
+///
+/// assert!(fs::file_exists(directory.join("artifact.bin.003")));
+///
+/// std::fs::remove_file(&path).unwrap();
+///
+/// assert_eq!(fs::join_parts(&manifest).unwrap(), path);
+/// assert_eq!(fs::read_all_bytes(&path).unwrap(), data);
+///
+/// fs::write_all_bytes(directory.join("artifact.bin.002"), &[0; 1000]).unwrap();
+///
+/// match fs::join_parts(&manifest) {
+///     Err(JoinError::CorruptPart { index, .. }) => assert_eq!(index, 1),
+///     x => panic!("unexpected result: {:?}", x),
+/// }
+/// ```
+pub fn split_file(path: impl AsRef<Path>, part_size: u64) -> Result<PathBuf, std::io::Error> {
+    let path = path.as_ref();
+
+    if part_size == 0 {
+        return Err(std::io::Error::new(
+            ErrorKind::InvalidInput,
+            "part size must be greater than zero",
+        ));
+    }
+
+    let name = file_name(path)?;
+    let directory = directory(path);
+    let file = File::open(path)?;
+    let size = file.metadata()?.len();
+    let count = size.div_ceil(part_size);
+    let digits = count.to_string().len().max(3);
+    let mut source = HashingReader::new(file, HashAlgorithm::Sha256);
+    let mut manifest = format!(
+        "version {}\nname {}\nsize {}\n",
+        MANIFEST_VERSION, name, size
+    );
+
+    for i in 0..count {
+        let part_name = format!("{}.{:0width$}", name, i + 1, width = digits);
+        let expected = part_size.min(size - i * part_size);
+        let mut part = HashingWriter::new(
+            File::create(directory.join(&part_name))?,
+            HashAlgorithm::Sha256,
+        );
+
+        if std::io::copy(&mut (&mut source).take(expected), &mut part)? != expected {
+            return Err(std::io::Error::new(
+                ErrorKind::UnexpectedEof,
+                "the file was truncated while being split",
+            ));
+        }
+
+        let (mut part, hashes) = part.finish();
+
+        part.flush()?;
+        manifest.push_str(&format!(
+            "part {} {} {}\n",
+            expected,
+            crate::fmt::to_hex(&hashes[0]),
+            part_name
+        ));
+    }
+
+    let (_, hashes) = source.finish();
+    let manifest_path = directory.join(format!("{}.manifest", name));
+
+    manifest.push_str(&format!("sha256 {}\n", crate::fmt::to_hex(&hashes[0])));
+    crate::fs::write_all_text(&manifest_path, manifest)?;
+
+    Ok(manifest_path)
+}
+
+/// verifies and joins the parts listed in `manifest`, returning the path of the joined file. the file is written next
+/// to the manifest, under the name recorded in it. see `split_file`.
+///
+/// fails with the index of the first missing or corrupt part. an existing file is only replaced once every part has
+/// been verified.
+pub fn join_parts(manifest: impl AsRef<Path>) -> Result<PathBuf, JoinError> {
+    let manifest = manifest.as_ref();
+    let parsed = Manifest::parse(&crate::fs::read_all_text(manifest)?)?;
+    let destination = directory(manifest).join(&parsed.name);
+
+    join(manifest, &parsed, &destination)?;
+
+    Ok(destination)
+}
+
+/// verifies and joins the parts listed in `manifest` into `destination`. see `join_parts`.
+pub fn join_parts_to(
+    manifest: impl AsRef<Path>,
+    destination: impl AsRef<Path>,
+) -> Result<(), JoinError> {
+    let manifest = manifest.as_ref();
+    let parsed = Manifest::parse(&crate::fs::read_all_text(manifest)?)?;
+
+    join(manifest, &parsed, destination.as_ref())
+}
+
+fn join(manifest: &Path, parsed: &Manifest, destination: &Path) -> Result<(), JoinError> {
+    let mut temporary = destination.as_os_str().to_owned();
+
+    temporary.push(".partial");
+
+    let temporary = PathBuf::from(temporary);
+    let result = join_into(manifest, parsed, &temporary);
+
+    match result {
+        Ok(()) => Ok(std::fs::rename(&temporary, destination)?),
+        Err(error) => {
+            std::fs::remove_file(&temporary).ok();
+            Err(error)
+        }
+    }
+}
+
+fn join_into(manifest: &Path, parsed: &Manifest, destination: &Path) -> Result<(), JoinError> {
+    let directory = directory(manifest);
+    let mut output = HashingWriter::new(File::create(destination)?, HashAlgorithm::Sha256);
+
+    for (index, part) in parsed.parts.iter().enumerate() {
+        let path = directory.join(&part.name);
+        let file = match File::open(&path) {
+            Ok(file) => file,
+            Err(error) if error.kind() == ErrorKind::NotFound => {
+                return Err(JoinError::MissingPart { index, path })
+            }
+            Err(error) => return Err(error.into()),
+        };
+
+        if file.metadata()?.len() != part.size {
+            return Err(JoinError::CorruptPart { index, path });
+        }
+
+        let mut input = HashingReader::new(file, HashAlgorithm::Sha256);
+
+        std::io::copy(&mut input, &mut output)?;
+
+        if *input.finish().1[0] != *part.hash {
+            return Err(JoinError::CorruptPart { index, path });
+        }
+    }
+
+    let (mut file, hashes) = output.finish();
+
+    file.flush()?;
+
+    match *hashes[0] == *parsed.hash {
+        true => Ok(()),
+        false => Err(JoinError::CorruptFile),
+    }
+}
+
+struct Manifest {
+    name: String,
+    hash: Vec<u8>,
+    parts: Vec<Part>,
+}
+
+struct Part {
+    name: String,
+    size: u64,
+    hash: Vec<u8>,
+}
+
+impl Manifest {
+    fn parse(text: &str) -> Result<Manifest, JoinError> {
+        let invalid = |reason: &str| JoinError::InvalidManifest(reason.to_owned());
+        let hash = |value: &str| crate::fmt::from_hex(value).map_err(|_| invalid("invalid hash"));
+        let number = |value: &str| value.parse::<u64>().map_err(|_| invalid("invalid size"));
+        let mut version = None;
+        let mut name = None;
+        let mut size = None;
+        let mut whole = None;
+        let mut parts = vec![];
+
+        for line in text.lines().filter(|x| !x.is_empty()) {
+            let (key, value) = line
+                .split_once(' ')
+                .ok_or_else(|| invalid("malformed line"))?;
+
+            match key {
+                "version" => version = Some(value),
+                "name" => name = Some(plain_name(value).ok_or_else(|| invalid("invalid name"))?),
+                "size" => size = Some(number(value)?),
+                "sha256" => whole = Some(hash(value)?),
+                "part" => {
+                    let mut fields = value.splitn(3, ' ');
+                    let mut field = || fields.next().ok_or_else(|| invalid("malformed part"));
+                    let size = number(field()?)?;
+                    let hash = hash(field()?)?;
+                    let name = plain_name(field()?).ok_or_else(|| invalid("invalid part name"))?;
+
+                    parts.push(Part { name, size, hash });
+                }
+                _ => return Err(invalid("unknown key")),
+            }
+        }
+
+        if version != Some(MANIFEST_VERSION) {
+            return Err(invalid("unsupported version"));
+        }
+
+        let size = size.ok_or_else(|| invalid("missing size"))?;
+
+        if parts.iter().map(|x| x.size).sum::<u64>() != size {
+            return Err(invalid("part sizes do not add up to the file size"));
+        }
+
+        Ok(Manifest {
+            name: name.ok_or_else(|| invalid("missing name"))?,
+            hash: whole.ok_or_else(|| invalid("missing hash"))?,
+            parts,
+        })
+    }
+}
+
+// returns `name` if it is a plain file name, so that a manifest cannot refer to files outside of its directory.
+fn plain_name(name: &str) -> Option<String> {
+    match Path::new(name).file_name() == Some(OsStr::new(name)) && !name.contains(['/', '\\']) {
+        true => Some(name.to_owned()),
+        false => None,
+    }
+}
+
+fn file_name(path: &Path) -> Result<String, std::io::Error> {
+    path.file_name()
+        .and_then(|x| x.to_str())
+        .filter(|x| !x.contains('\n'))
+        .map(|x| x.to_owned())
+        .ok_or_else(|| {
+            std::io::Error::new(
+                ErrorKind::InvalidInput,
+                "path must end in a valid utf-8 file name",
+            )
+        })
+}
+
+fn directory(path: &Path) -> &Path {
+    path.parent().unwrap_or_else(|| Path::new(""))
+}
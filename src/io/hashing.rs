@@ -0,0 +1,130 @@
+use std::io::{Read, Write};
+
+use crate::crypto::{Hash, HashAlgorithm, IncrementalHash};
+
+macro_rules! hashing_methods {
+    ($type: ident, $inner: ident) => {
+        impl<$inner> $type<$inner> {
+            /// creates a new adapter which digests data with `algorithm` as it streams through.
+            pub fn new(inner: $inner, algorithm: HashAlgorithm) -> $type<$inner> {
+                $type::with_algorithms(inner, Some(algorithm))
+            }
+
+            /// creates a new adapter which digests data with several `algorithms` at once.
+            pub fn with_algorithms(
+                inner: $inner,
+                algorithms: impl IntoIterator<Item = HashAlgorithm>,
+            ) -> $type<$inner> {
+                $type {
+                    inner,
+                    hashes: algorithms.into_iter().map(IncrementalHash::new).collect(),
+                }
+            }
+
+            /// consumes this adapter, returning the inner stream and the final hash for each algorithm, in the order
+            /// the algorithms were given.
+            pub fn finish(self) -> ($inner, Vec<Hash>) {
+                let hashes = self.hashes.into_iter().map(|x| x.finish()).collect();
+
+                (self.inner, hashes)
+            }
+
+            pub fn get_ref(&self) -> &$inner {
+                &self.inner
+            }
+
+            /// returns a mutable reference to the inner stream. data read from or written to it directly will not be
+            /// hashed.
+            pub fn get_mut(&mut self) -> &mut $inner {
+                &mut self.inner
+            }
+
+            fn update(&mut self, data: &[u8]) {
+                for hash in &mut self.hashes {
+                    hash.update(data);
+                }
+            }
+        }
+    };
+}
+
+/// a reader adapter which hashes all data read through it.
+///
+/// # examples.
+///
+/// ```
+/// # use ari::crypto::{hash_slice, HashAlgorithm};
+/// # use ari::io::HashingReader;
+/// # use std::io::Read;
+///
+/// let data = b"hello, world.";
+/// let mut reader = HashingReader::with_algorithms(&data[..], vec![HashAlgorithm::Sha256, HashAlgorithm::Sha512]);
+/// let mut copy = vec![];
+///
+/// reader.read_to_end(&mut copy).unwrap();
+///
+/// let (_, hashes) = reader.finish();
+///
+/// assert_eq!(copy, data);
+/// assert_eq!(&*hashes[0], &*hash_slice(data, HashAlgorithm::Sha256));
+/// assert_eq!(&*hashes[1], &*hash_slice(data, HashAlgorithm::Sha512));
+/// ```
+pub struct HashingReader<R> {
+    inner: R,
+    hashes: Vec<IncrementalHash>,
+}
+
+hashing_methods!(HashingReader, R);
+
+impl<R> Read for HashingReader<R>
+where
+    R: Read,
+{
+    fn read(&mut self, buffer: &mut [u8]) -> Result<usize, std::io::Error> {
+        let read = self.inner.read(buffer)?;
+
+        self.update(&buffer[..read]);
+        Ok(read)
+    }
+}
+
+/// a writer adapter which hashes all data written through it.
+///
+/// # examples.
+///
+/// ```
+/// # use ari::crypto::{hash_slice, HashAlgorithm};
+/// # use ari::io::HashingWriter;
+/// # use std::io::Write;
+///
+/// let mut writer = HashingWriter::new(vec![], HashAlgorithm::Sha256);
+///
+/// writer.write_all(b"hello, world.").unwrap();
+///
+/// let (data, hashes) = writer.finish();
+///
+/// assert_eq!(data, b"hello, world.");
+/// assert_eq!(&*hashes[0], &*hash_slice(b"hello, world.", HashAlgorithm::Sha256));
+/// ```
+pub struct HashingWriter<W> {
+    inner: W,
+    hashes: Vec<IncrementalHash>,
+}
+
+hashing_methods!(HashingWriter, W);
+
+impl<W> Write for HashingWriter<W>
+where
+    W: Write,
+{
+    fn write(&mut self, buffer: &[u8]) -> Result<usize, std::io::Error> {
+        let written = self.inner.write(buffer)?;
+
+        self.update(&buffer[..written]);
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> Result<(), std::io::Error> {
+        self.inner.flush()
+    }
+}
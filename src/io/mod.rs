@@ -1,7 +1,50 @@
 pub mod stdin;
 
-use std::io::{Read, Seek, SeekFrom};
-use std::mem::MaybeUninit;
+mod hashing;
+mod pipe;
+mod progress;
+mod substream;
+mod sys;
+
+pub use self::hashing::*;
+pub use self::pipe::*;
+pub use self::progress::*;
+pub use self::substream::*;
+
+use std::io::{ErrorKind, Read, Seek, SeekFrom, Write};
+use std::ops::{Deref, DerefMut};
+
+macro_rules! read_primitive_methods {
+    ($($type: ty => $le: ident, $be: ident;)*) => {
+        $(
+            #[inline]
+            fn $le(&mut self) -> Result<$type, std::io::Error> {
+                ReadExt::read_array(self).map(<$type>::from_le_bytes)
+            }
+
+            #[inline]
+            fn $be(&mut self) -> Result<$type, std::io::Error> {
+                ReadExt::read_array(self).map(<$type>::from_be_bytes)
+            }
+        )*
+    };
+}
+
+macro_rules! write_primitive_methods {
+    ($($type: ty => $le: ident, $be: ident;)*) => {
+        $(
+            #[inline]
+            fn $le(&mut self, value: $type) -> Result<(), std::io::Error> {
+                self.write_all(&value.to_le_bytes())
+            }
+
+            #[inline]
+            fn $be(&mut self, value: $type) -> Result<(), std::io::Error> {
+                self.write_all(&value.to_be_bytes())
+            }
+        )*
+    };
+}
 
 pub trait ReadExt: Read {
     #[inline]
@@ -35,36 +78,402 @@ pub trait ReadExt: Read {
         Ok(data)
     }
 
-    // :: const generics workarounds
-    //     when const generics arrive, refactor into generic code.
+    /// reads exactly `N` bytes into an array.
+    ///
+    /// # examples.
+    ///
+    /// ```
+    /// # use ari::io::ReadExt;
+    ///
+    /// let mut stream: &[u8] = &[1, 2, 3, 4, 5];
+    ///
+    /// assert_eq!(stream.read_array::<4>().unwrap(), [1, 2, 3, 4]);
+    /// assert_eq!(stream.read_array::<4>().is_err(), true);
+    /// ```
+    #[inline]
+    fn read_array<const N: usize>(&mut self) -> Result<[u8; N], std::io::Error> {
+        let mut data = [0; N];
+
+        self.read_exact(&mut data)?;
+        Ok(data)
+    }
 
     #[inline]
     fn read_bytes_16(&mut self) -> Result<[u8; 16], std::io::Error> {
-        unsafe {
-            let mut data = MaybeUninit::<[u8; 16]>::uninit();
-
-            self.read_exact(&mut *data.as_mut_ptr())?;
-            Ok(data.assume_init())
-        }
+        ReadExt::read_array(self)
     }
 
     #[inline]
     fn read_bytes_32(&mut self) -> Result<[u8; 32], std::io::Error> {
-        unsafe {
-            let mut data = MaybeUninit::<[u8; 32]>::uninit();
+        ReadExt::read_array(self)
+    }
+
+    #[inline]
+    fn read_u8(&mut self) -> Result<u8, std::io::Error> {
+        ReadExt::read_array(self).map(u8::from_le_bytes)
+    }
+
+    #[inline]
+    fn read_i8(&mut self) -> Result<i8, std::io::Error> {
+        ReadExt::read_array(self).map(i8::from_le_bytes)
+    }
+
+    read_primitive_methods! {
+        u16 => read_u16_le, read_u16_be;
+        u32 => read_u32_le, read_u32_be;
+        u64 => read_u64_le, read_u64_be;
+        i16 => read_i16_le, read_i16_be;
+        i32 => read_i32_le, read_i32_be;
+        i64 => read_i64_le, read_i64_be;
+        f32 => read_f32_le, read_f32_be;
+        f64 => read_f64_le, read_f64_be;
+    }
+
+    /// reads an unsigned leb128 variable length integer.
+    ///
+    /// fails with `ErrorKind::InvalidData` if the encoded value does not fit in a `u64`.
+    ///
+    /// # examples.
+    ///
+    /// ```
+    /// # use ari::io::ReadExt;
+    ///
+    /// let mut stream: &[u8] = &[0xe5, 0x8e, 0x26];
+    ///
+    /// assert_eq!(stream.read_uleb128().unwrap(), 624_485);
+    /// ```
+    fn read_uleb128(&mut self) -> Result<u64, std::io::Error> {
+        let mut value = 0u64;
+        let mut shift = 0;
 
-            self.read_exact(&mut *data.as_mut_ptr())?;
-            Ok(data.assume_init())
+        loop {
+            let byte = self.read_u8()?;
+            let bits = (byte & 0x7f) as u64;
+
+            if (shift == 63 && bits > 1) || shift > 63 {
+                return Err(std::io::Error::new(
+                    ErrorKind::InvalidData,
+                    "leb128 value overflows a u64",
+                ));
+            }
+
+            value |= bits << shift;
+            shift += 7;
+
+            if byte & 0x80 == 0 {
+                return Ok(value);
+            }
         }
     }
+
+    /// reads a signed leb128 variable length integer.
+    ///
+    /// fails with `ErrorKind::InvalidData` if the encoded value does not fit in an `i64`.
+    ///
+    /// # examples.
+    ///
+    /// ```
+    /// # use ari::io::ReadExt;
+    ///
+    /// let mut stream: &[u8] = &[0xc0, 0xbb, 0x78];
+    ///
+    /// assert_eq!(stream.read_sleb128().unwrap(), -123_456);
+    /// ```
+    fn read_sleb128(&mut self) -> Result<i64, std::io::Error> {
+        let mut value = 0i64;
+        let mut shift = 0;
+
+        loop {
+            let byte = self.read_u8()?;
+            let bits = (byte & 0x7f) as i64;
+
+            if (shift == 63 && bits != 0 && bits != 0x7f) || shift > 63 {
+                return Err(std::io::Error::new(
+                    ErrorKind::InvalidData,
+                    "leb128 value overflows an i64",
+                ));
+            }
+
+            value |= bits << shift;
+            shift += 7;
+
+            if byte & 0x80 == 0 {
+                if shift < 64 && byte & 0x40 != 0 {
+                    value |= -1 << shift;
+                }
+
+                return Ok(value);
+            }
+        }
+    }
+
+    /// reads a byte vector prefixed with its unsigned leb128 encoded length, as written by
+    /// `WriteExt::write_prefixed_bytes`.
+    fn read_prefixed_bytes(&mut self) -> Result<Vec<u8>, std::io::Error> {
+        let length = self.read_uleb128()?;
+        let mut data = vec![];
+
+        // read through `take` rather than allocating `length` up front, as `length` may be untrusted.
+        self.take(length).read_to_end(&mut data)?;
+
+        match data.len() as u64 == length {
+            true => Ok(data),
+            false => Err(ErrorKind::UnexpectedEof.into()),
+        }
+    }
+
+    /// reads a utf-8 string prefixed with its unsigned leb128 encoded length, as written by
+    /// `WriteExt::write_prefixed_str`.
+    fn read_prefixed_string(&mut self) -> Result<String, std::io::Error> {
+        let data = self.read_prefixed_bytes()?;
+
+        String::from_utf8(data).map_err(|_| ErrorKind::InvalidData.into())
+    }
 }
 
 impl<T> ReadExt for T where T: Read + ?Sized {}
 
+pub trait WriteExt: Write {
+    #[inline]
+    fn write_u8(&mut self, value: u8) -> Result<(), std::io::Error> {
+        self.write_all(&[value])
+    }
+
+    #[inline]
+    fn write_i8(&mut self, value: i8) -> Result<(), std::io::Error> {
+        self.write_all(&value.to_le_bytes())
+    }
+
+    write_primitive_methods! {
+        u16 => write_u16_le, write_u16_be;
+        u32 => write_u32_le, write_u32_be;
+        u64 => write_u64_le, write_u64_be;
+        i16 => write_i16_le, write_i16_be;
+        i32 => write_i32_le, write_i32_be;
+        i64 => write_i64_le, write_i64_be;
+        f32 => write_f32_le, write_f32_be;
+        f64 => write_f64_le, write_f64_be;
+    }
+
+    /// writes an unsigned leb128 variable length integer.
+    ///
+    /// # examples.
+    ///
+    /// ```
+    /// # use ari::io::WriteExt;
+    ///
+    /// let mut data = vec![];
+    ///
+    /// data.write_uleb128(624_485).unwrap();
+    ///
+    /// assert_eq!(data, [0xe5, 0x8e, 0x26]);
+    /// ```
+    fn write_uleb128(&mut self, mut value: u64) -> Result<(), std::io::Error> {
+        let mut data = [0u8; 10];
+        let mut length = 0;
+
+        loop {
+            let byte = (value & 0x7f) as u8;
+
+            value >>= 7;
+
+            match value {
+                0 => {
+                    data[length] = byte;
+                    length += 1;
+                    break;
+                }
+                _ => {
+                    data[length] = byte | 0x80;
+                    length += 1;
+                }
+            }
+        }
+
+        self.write_all(&data[..length])
+    }
+
+    /// writes a signed leb128 variable length integer.
+    ///
+    /// # examples.
+    ///
+    /// ```
+    /// # use ari::io::{ReadExt, WriteExt};
+    ///
+    /// let mut data = vec![];
+    ///
+    /// data.write_sleb128(-123_456).unwrap();
+    ///
+    /// assert_eq!(data, [0xc0, 0xbb, 0x78]);
+    /// assert_eq!(data.as_slice().read_sleb128().unwrap(), -123_456);
+    /// ```
+    fn write_sleb128(&mut self, mut value: i64) -> Result<(), std::io::Error> {
+        let mut data = [0u8; 10];
+        let mut length = 0;
+
+        loop {
+            let byte = (value & 0x7f) as u8;
+
+            value >>= 7;
+
+            let done = (value == 0 && byte & 0x40 == 0) || (value == -1 && byte & 0x40 != 0);
+
+            data[length] = if done { byte } else { byte | 0x80 };
+            length += 1;
+
+            if done {
+                break;
+            }
+        }
+
+        self.write_all(&data[..length])
+    }
+
+    /// writes `data` prefixed with its unsigned leb128 encoded length.
+    ///
+    /// # examples.
+    ///
+    /// ```
+    /// # use ari::io::{ReadExt, WriteExt};
+    ///
+    /// let mut data = vec![];
+    ///
+    /// data.write_prefixed_str("ari").unwrap();
+    /// data.write_u32_be(0xdeadbeef).unwrap();
+    ///
+    /// assert_eq!(data, [3, b'a', b'r', b'i', 0xde, 0xad, 0xbe, 0xef]);
+    ///
+    /// let mut stream = data.as_slice();
+    ///
+    /// assert_eq!(stream.read_prefixed_string().unwrap(), "ari");
+    /// assert_eq!(stream.read_u32_be().unwrap(), 0xdeadbeef);
+    /// ```
+    fn write_prefixed_bytes(&mut self, data: &[u8]) -> Result<(), std::io::Error> {
+        self.write_uleb128(data.len() as u64)?;
+        self.write_all(data)
+    }
+
+    /// writes the utf-8 bytes of `string` prefixed with their unsigned leb128 encoded length.
+    fn write_prefixed_str(&mut self, string: &str) -> Result<(), std::io::Error> {
+        self.write_prefixed_bytes(string.as_bytes())
+    }
+}
+
+impl<T> WriteExt for T where T: Write + ?Sized {}
+
 pub trait SeekExt: Seek {
     fn position(&mut self) -> Result<u64, std::io::Error> {
         self.seek(SeekFrom::Current(0))
     }
+
+    /// returns the length of this stream in bytes. the current position is preserved.
+    ///
+    /// # examples.
+    ///
+    /// ```
+    /// # use ari::io::SeekExt;
+    /// # use std::io::{Cursor, Seek, SeekFrom};
+    ///
+    /// let mut stream = Cursor::new(vec![0u8; 16]);
+    ///
+    /// stream.seek(SeekFrom::Start(4)).unwrap();
+    ///
+    /// assert_eq!(stream.stream_len().unwrap(), 16);
+    /// assert_eq!(stream.position(), 4);
+    /// ```
+    fn stream_len(&mut self) -> Result<u64, std::io::Error> {
+        let position = self.position()?;
+        let length = self.seek(SeekFrom::End(0))?;
+
+        if position != length {
+            self.seek(SeekFrom::Start(position))?;
+        }
+
+        Ok(length)
+    }
+
+    /// returns a guard which dereferences to this stream, and seeks it back to its current position when dropped.
+    ///
+    /// # examples.
+    ///
+    /// ```
+    /// # use ari::io::{ReadExt, SeekExt};
+    /// # use std::io::{Cursor, Seek, SeekFrom};
+    ///
+    /// let mut stream = Cursor::new(vec![1u8, 2, 3, 4]);
+    ///
+    /// {
+    ///     let mut guard = stream.rewind_on_drop().unwrap();
+    ///
+    ///     guard.seek(SeekFrom::End(-1)).unwrap();
+    ///     assert_eq!(guard.read_u8().unwrap(), 4);
+    /// }
+    ///
+    /// assert_eq!(stream.read_u8().unwrap(), 1);
+    /// ```
+    fn rewind_on_drop(&mut self) -> Result<RewindGuard<'_, Self>, std::io::Error> {
+        let position = self.position()?;
+
+        Ok(RewindGuard {
+            stream: self,
+            position,
+        })
+    }
 }
 
 impl<T> SeekExt for T where T: Seek + ?Sized {}
+
+/// seeks a stream back to a saved position when dropped. see `SeekExt::rewind_on_drop`.
+///
+/// errors while seeking back are ignored. call `rewind` to observe them.
+pub struct RewindGuard<'a, S>
+where
+    S: Seek + ?Sized,
+{
+    stream: &'a mut S,
+    position: u64,
+}
+
+impl<'a, S> RewindGuard<'a, S>
+where
+    S: Seek + ?Sized,
+{
+    /// returns the position that the stream will be returned to.
+    pub fn position(&self) -> u64 {
+        self.position
+    }
+
+    /// seeks the stream back to the saved position now, returning any error.
+    pub fn rewind(&mut self) -> Result<(), std::io::Error> {
+        self.stream.seek(SeekFrom::Start(self.position)).map(|_| ())
+    }
+}
+
+impl<'a, S> Deref for RewindGuard<'a, S>
+where
+    S: Seek + ?Sized,
+{
+    type Target = S;
+
+    fn deref(&self) -> &S {
+        self.stream
+    }
+}
+
+impl<'a, S> DerefMut for RewindGuard<'a, S>
+where
+    S: Seek + ?Sized,
+{
+    fn deref_mut(&mut self) -> &mut S {
+        self.stream
+    }
+}
+
+impl<'a, S> Drop for RewindGuard<'a, S>
+where
+    S: Seek + ?Sized,
+{
+    fn drop(&mut self) {
+        let _ = self.rewind();
+    }
+}
@@ -0,0 +1,219 @@
+use parking_lot::Mutex;
+use std::collections::VecDeque;
+use std::io::{ErrorKind, Read, Write};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use crate::sync::{AutoResetEvent, ResetEvent};
+
+/// creates an in-memory pipe backed by a ring buffer of `capacity` bytes, returning its writing and reading halves.
+///
+/// writes block while the buffer is full and reads block while it is empty. once the writer is dropped, reads drain
+/// the remaining data and then return end of file. once the reader is dropped, writes fail with
+/// `ErrorKind::BrokenPipe`. a `capacity` of zero is treated as one.
+///
+/// # examples.
+///
+/// ```
+/// # use std::io::{Read, Write};
+///
+/// let (mut writer, mut reader) = ari::io::pipe(4);
+///
+/// let producer = std::thread::spawn(move || {
+///     for i in 0..100u8 {
+///         writer.write_all(&[i]).unwrap();
+///     }
+/// });
+///
+/// let mut data = vec![];
+///
+/// reader.read_to_end(&mut data).unwrap();
+/// producer.join().unwrap();
+///
+/// assert_eq!(data, (0..100u8).collect::<Vec<_>>());
+/// ```
+pub fn pipe(capacity: usize) -> (PipeWriter, PipeReader) {
+    let shared = Arc::new(Shared {
+        state: Mutex::new(State {
+            buffer: VecDeque::with_capacity(capacity.max(1)),
+            capacity: capacity.max(1),
+            reader: true,
+            writer: true,
+        }),
+        readable: AutoResetEvent::new(false),
+        writable: AutoResetEvent::new(false),
+    });
+
+    let writer = PipeWriter {
+        shared: shared.clone(),
+        timeout: None,
+    };
+
+    let reader = PipeReader {
+        shared,
+        timeout: None,
+    };
+
+    (writer, reader)
+}
+
+struct State {
+    buffer: VecDeque<u8>,
+    capacity: usize,
+    reader: bool,
+    writer: bool,
+}
+
+struct Shared {
+    state: Mutex<State>,
+    readable: AutoResetEvent,
+    writable: AutoResetEvent,
+}
+
+/// the writing half of a `pipe`.
+pub struct PipeWriter {
+    shared: Arc<Shared>,
+    timeout: Option<Duration>,
+}
+
+impl PipeWriter {
+    /// sets how long a write may block while the buffer is full. a write which times out fails with
+    /// `ErrorKind::TimedOut`. `None`, the default, blocks indefinitely.
+    pub fn set_write_timeout(&mut self, timeout: Option<Duration>) {
+        self.timeout = timeout;
+    }
+
+    pub fn write_timeout(&self) -> Option<Duration> {
+        self.timeout
+    }
+}
+
+impl Write for PipeWriter {
+    fn write(&mut self, data: &[u8]) -> Result<usize, std::io::Error> {
+        if data.is_empty() {
+            return Ok(0);
+        }
+
+        let deadline = self.timeout.map(|x| Instant::now() + x);
+
+        loop {
+            {
+                let mut state = self.shared.state.lock();
+
+                if !state.reader {
+                    return Err(ErrorKind::BrokenPipe.into());
+                }
+
+                let available = state.capacity - state.buffer.len();
+
+                if available > 0 {
+                    let length = available.min(data.len());
+
+                    state.buffer.extend(&data[..length]);
+                    self.shared.readable.set();
+
+                    return Ok(length);
+                }
+            }
+
+            wait(&self.shared.writable, deadline)?;
+        }
+    }
+
+    fn flush(&mut self) -> Result<(), std::io::Error> {
+        Ok(())
+    }
+}
+
+impl Drop for PipeWriter {
+    fn drop(&mut self) {
+        self.shared.state.lock().writer = false;
+        self.shared.readable.set();
+    }
+}
+
+/// the reading half of a `pipe`.
+pub struct PipeReader {
+    shared: Arc<Shared>,
+    timeout: Option<Duration>,
+}
+
+impl PipeReader {
+    /// sets how long a read may block while the buffer is empty. a read which times out fails with
+    /// `ErrorKind::TimedOut`. `None`, the default, blocks indefinitely.
+    ///
+    /// # examples.
+    ///
+    /// ```
+    /// # use std::io::Read;
+    /// # use std::time::Duration;
+    ///
+    /// let (_writer, mut reader) = ari::io::pipe(16);
+    ///
+    /// reader.set_read_timeout(Some(Duration::from_millis(10)));
+    ///
+    /// let error = reader.read(&mut [0; 4]).unwrap_err();
+    ///
+    /// assert_eq!(error.kind(), std::io::ErrorKind::TimedOut);
+    /// ```
+    pub fn set_read_timeout(&mut self, timeout: Option<Duration>) {
+        self.timeout = timeout;
+    }
+
+    pub fn read_timeout(&self) -> Option<Duration> {
+        self.timeout
+    }
+}
+
+impl Read for PipeReader {
+    fn read(&mut self, buffer: &mut [u8]) -> Result<usize, std::io::Error> {
+        if buffer.is_empty() {
+            return Ok(0);
+        }
+
+        let deadline = self.timeout.map(|x| Instant::now() + x);
+
+        loop {
+            {
+                let mut state = self.shared.state.lock();
+
+                if !state.buffer.is_empty() {
+                    let length = state.buffer.len().min(buffer.len());
+
+                    for (target, source) in buffer.iter_mut().zip(state.buffer.drain(..length)) {
+                        *target = source;
+                    }
+
+                    self.shared.writable.set();
+
+                    return Ok(length);
+                }
+
+                if !state.writer {
+                    return Ok(0);
+                }
+            }
+
+            wait(&self.shared.readable, deadline)?;
+        }
+    }
+}
+
+impl Drop for PipeReader {
+    fn drop(&mut self) {
+        self.shared.state.lock().reader = false;
+        self.shared.writable.set();
+    }
+}
+
+// waits for `event` to be signalled, failing with `ErrorKind::TimedOut` if `deadline` passes first.
+fn wait(event: &AutoResetEvent, deadline: Option<Instant>) -> Result<(), std::io::Error> {
+    match deadline {
+        Some(deadline) if !event.wait_until(deadline) => Err(ErrorKind::TimedOut.into()),
+        Some(_) => Ok(()),
+        None => {
+            event.wait();
+            Ok(())
+        }
+    }
+}
@@ -0,0 +1,339 @@
+use std::collections::VecDeque;
+use std::fmt::{Debug, Display, Formatter};
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::time::{Duration, Instant};
+
+use crate::fmt::{HumanBytes, HumanDuration};
+use crate::time::Stopwatch;
+
+/// the default duration over which throughput is measured.
+const DEFAULT_WINDOW: Duration = Duration::from_secs(5);
+
+/// the minimum duration between two throughput samples.
+const SAMPLE_INTERVAL: Duration = Duration::from_millis(50);
+
+/// a snapshot of the progress of a `ProgressReader` or `ProgressWriter`.
+///
+/// displays as a single progress line, for example `1.50 MiB / 10.00 MiB (15%), 2.00 MiB/s, 4 seconds remaining`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Progress {
+    /// the number of bytes transferred so far.
+    pub bytes: u64,
+
+    /// the expected total number of bytes, if known.
+    pub total: Option<u64>,
+
+    /// the time elapsed since the stream was wrapped.
+    pub elapsed: Duration,
+
+    /// the recent throughput in bytes per second, measured over a sliding window.
+    pub rate: f64,
+}
+
+impl Progress {
+    /// returns the fraction of `total` transferred, in `[0, 1]`, or `None` if the total is unknown.
+    pub fn fraction(&self) -> Option<f64> {
+        match self.total {
+            Some(0) => Some(1.0),
+            Some(total) => Some((self.bytes as f64 / total as f64).min(1.0)),
+            None => None,
+        }
+    }
+
+    /// returns the estimated time remaining, or `None` if the total is unknown or nothing has been transferred yet.
+    pub fn remaining(&self) -> Option<Duration> {
+        let total = self.total?;
+        let left = total.saturating_sub(self.bytes);
+
+        match left {
+            0 => Some(Duration::default()),
+            _ if self.rate > 0.0 => Some(Duration::from_secs_f64(left as f64 / self.rate)),
+            _ => None,
+        }
+    }
+}
+
+impl Display for Progress {
+    fn fmt(&self, formatter: &mut Formatter) -> Result<(), std::fmt::Error> {
+        let rate = HumanBytes(self.rate as u64);
+
+        match (self.total, self.fraction()) {
+            (Some(total), Some(fraction)) => write!(
+                formatter,
+                "{} / {} ({:.0}%), {}/s",
+                HumanBytes(self.bytes),
+                HumanBytes(total),
+                fraction * 100.0,
+                rate
+            )?,
+            _ => write!(formatter, "{}, {}/s", HumanBytes(self.bytes), rate)?,
+        }
+
+        match self.remaining() {
+            Some(remaining) => write!(formatter, ", {} remaining", HumanDuration(remaining)),
+            None => write!(formatter, ", {} elapsed", HumanDuration(self.elapsed)),
+        }
+    }
+}
+
+type ProgressCallback = Box<dyn FnMut(&Progress) + Send>;
+
+// the byte counting, throughput and callback state shared by `ProgressReader` and `ProgressWriter`.
+struct Tracker {
+    bytes: u64,
+    total: Option<u64>,
+    stopwatch: Stopwatch,
+    window: Duration,
+    samples: VecDeque<(Instant, u64)>,
+    callback: Option<ProgressCallback>,
+    interval: Duration,
+    reported: Option<Instant>,
+}
+
+impl Tracker {
+    fn new(total: Option<u64>) -> Tracker {
+        let mut samples = VecDeque::new();
+
+        samples.push_back((Instant::now(), 0));
+
+        Tracker {
+            bytes: 0,
+            total,
+            stopwatch: Stopwatch::started(),
+            window: DEFAULT_WINDOW,
+            samples,
+            callback: None,
+            interval: Duration::default(),
+            reported: None,
+        }
+    }
+
+    fn add(&mut self, count: usize) {
+        let now = Instant::now();
+
+        self.bytes += count as u64;
+
+        if self
+            .samples
+            .back()
+            .map(|x| now - x.0 >= SAMPLE_INTERVAL)
+            .unwrap_or(true)
+        {
+            self.samples.push_back((now, self.bytes));
+        }
+
+        // keep one sample older than the window, so that the window is always fully covered.
+        while self.samples.len() > 2 && now - self.samples[1].0 > self.window {
+            self.samples.pop_front();
+        }
+
+        let due = match self.reported {
+            Some(reported) => now - reported >= self.interval,
+            None => true,
+        };
+
+        if due && self.callback.is_some() {
+            self.report(now);
+        }
+    }
+
+    fn report(&mut self, now: Instant) {
+        let progress = self.progress();
+
+        if let Some(callback) = self.callback.as_mut() {
+            self.reported = Some(now);
+            callback(&progress);
+        }
+    }
+
+    fn rate(&self) -> f64 {
+        let now = Instant::now();
+        let (instant, bytes) = self.samples.front().copied().unwrap_or((now, 0));
+        let span = (now - instant).as_secs_f64();
+
+        match span > 0.0 {
+            true => (self.bytes - bytes) as f64 / span,
+            false => 0.0,
+        }
+    }
+
+    fn progress(&self) -> Progress {
+        Progress {
+            bytes: self.bytes,
+            total: self.total,
+            elapsed: self.stopwatch.elapsed(),
+            rate: self.rate(),
+        }
+    }
+}
+
+macro_rules! progress_methods {
+    ($type: ident, $inner: ident) => {
+        impl<$inner> $type<$inner> {
+            pub fn new(inner: $inner) -> $type<$inner> {
+                $type {
+                    inner,
+                    tracker: Tracker::new(None),
+                }
+            }
+
+            /// creates a new adapter which estimates time remaining against `total` bytes.
+            pub fn with_total(inner: $inner, total: u64) -> $type<$inner> {
+                $type {
+                    inner,
+                    tracker: Tracker::new(Some(total)),
+                }
+            }
+
+            /// invokes `callback` as bytes pass through, at most once per `interval`.
+            pub fn on_progress(
+                mut self,
+                interval: Duration,
+                callback: impl FnMut(&Progress) + Send + 'static,
+            ) -> $type<$inner> {
+                self.tracker.interval = interval;
+                self.tracker.callback = Some(Box::new(callback));
+                self
+            }
+
+            /// sets the duration over which throughput is measured. defaults to five seconds.
+            pub fn set_window(&mut self, window: Duration) {
+                self.tracker.window = window;
+            }
+
+            pub fn set_total(&mut self, total: Option<u64>) {
+                self.tracker.total = total;
+            }
+
+            /// returns the number of bytes that have passed through this adapter.
+            pub fn transferred(&self) -> u64 {
+                self.tracker.bytes
+            }
+
+            /// returns a snapshot of the current progress.
+            pub fn progress(&self) -> Progress {
+                self.tracker.progress()
+            }
+
+            /// invokes the progress callback immediately, regardless of throttling. useful for a final report.
+            pub fn report(&mut self) {
+                self.tracker.report(Instant::now());
+            }
+
+            pub fn get_ref(&self) -> &$inner {
+                &self.inner
+            }
+
+            pub fn get_mut(&mut self) -> &mut $inner {
+                &mut self.inner
+            }
+
+            pub fn into_inner(self) -> $inner {
+                self.inner
+            }
+        }
+
+        impl<$inner> Debug for $type<$inner>
+        where
+            $inner: Debug,
+        {
+            fn fmt(&self, formatter: &mut Formatter) -> Result<(), std::fmt::Error> {
+                formatter
+                    .debug_struct(stringify!($type))
+                    .field("inner", &self.inner)
+                    .field("progress", &self.progress())
+                    .finish()
+            }
+        }
+
+        impl<$inner> Seek for $type<$inner>
+        where
+            $inner: Seek,
+        {
+            /// seeks the inner stream. seeking does not change the number of bytes counted as transferred.
+            fn seek(&mut self, position: SeekFrom) -> Result<u64, std::io::Error> {
+                self.inner.seek(position)
+            }
+        }
+    };
+}
+
+/// a reader adapter which tracks the number of bytes read, the throughput and the estimated time remaining.
+///
+/// # examples.
+///
+/// ```
+/// # use ari::io::ProgressReader;
+/// # use std::io::Read;
+/// # use std::sync::{Arc, Mutex};
+/// # use std::time::Duration;
+///
+/// let data = vec![0u8; 4096];
+/// let reports = Arc::new(Mutex::new(vec![]));
+/// let sink = reports.clone();
+///
+/// let mut reader = ProgressReader::with_total(data.as_slice(), 4096)
+///     .on_progress(Duration::default(), move |x| sink.lock().unwrap().push(x.bytes));
+///
+/// std::io::copy(&mut reader, &mut std::io::sink()).unwrap();
+///
+/// assert_eq!(reader.transferred(), 4096);
+/// assert_eq!(reader.progress().fraction(), Some(1.0));
+/// assert_eq!(reports.lock().unwrap().last(), Some(&4096));
+/// ```
+pub struct ProgressReader<R> {
+    inner: R,
+    tracker: Tracker,
+}
+
+progress_methods!(ProgressReader, R);
+
+impl<R> Read for ProgressReader<R>
+where
+    R: Read,
+{
+    fn read(&mut self, buffer: &mut [u8]) -> Result<usize, std::io::Error> {
+        let read = self.inner.read(buffer)?;
+
+        self.tracker.add(read);
+        Ok(read)
+    }
+}
+
+/// a writer adapter which tracks the number of bytes written, the throughput and the estimated time remaining.
+///
+/// # examples.
+///
+/// ```
+/// # use ari::io::ProgressWriter;
+/// # use std::io::Write;
+///
+/// let mut writer = ProgressWriter::new(vec![]);
+///
+/// writer.write_all(b"hello").unwrap();
+///
+/// assert_eq!(writer.transferred(), 5);
+/// assert_eq!(writer.into_inner(), b"hello");
+/// ```
+pub struct ProgressWriter<W> {
+    inner: W,
+    tracker: Tracker,
+}
+
+progress_methods!(ProgressWriter, W);
+
+impl<W> Write for ProgressWriter<W>
+where
+    W: Write,
+{
+    fn write(&mut self, buffer: &[u8]) -> Result<usize, std::io::Error> {
+        let written = self.inner.write(buffer)?;
+
+        self.tracker.add(written);
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> Result<(), std::io::Error> {
+        self.inner.flush()
+    }
+}
@@ -1,4 +1,4 @@
-use std::io::Read;
+use std::io::{BufRead, ErrorKind, IsTerminal, Read, Write};
 
 /// reads stdin until it encounters a new line, consuming it.
 pub fn read_enter_key() -> Result<(), std::io::Error> {
@@ -14,3 +14,198 @@ pub fn read_enter_key() -> Result<(), std::io::Error> {
         }
     }
 }
+
+/// writes `message` to stderr, then reads a line from stdin without its line ending.
+///
+/// fails with `ErrorKind::UnexpectedEof` if stdin is closed before a line is entered.
+pub fn prompt_line(message: &str) -> Result<String, std::io::Error> {
+    prompt_line_with(
+        &mut std::io::stdin().lock(),
+        &mut std::io::stderr(),
+        message,
+    )
+}
+
+/// writes `message` to `output`, then reads a line from `input`. see `prompt_line`.
+///
+/// # examples.
+///
+/// ```
+/// # use ari::io::stdin;
+///
+/// let mut output = vec![];
+/// let name = stdin::prompt_line_with(&mut &b"ari\r\n"[..], &mut output, "name: ").unwrap();
+///
+/// assert_eq!(name, "ari");
+/// assert_eq!(output, b"name: ");
+/// ```
+pub fn prompt_line_with(
+    input: &mut impl BufRead,
+    output: &mut impl Write,
+    message: &str,
+) -> Result<String, std::io::Error> {
+    write!(output, "{}", message)?;
+    output.flush()?;
+
+    read_line(input)
+}
+
+/// asks a yes or no question on stderr, returning the answer read from stdin.
+///
+/// `default` is returned when the user enters an empty line, or when stdin is closed. any other answer besides `y`,
+/// `yes`, `n` or `no` (case insensitively) asks the question again.
+pub fn prompt_yes_no(message: &str, default: bool) -> Result<bool, std::io::Error> {
+    prompt_yes_no_with(
+        &mut std::io::stdin().lock(),
+        &mut std::io::stderr(),
+        message,
+        default,
+    )
+}
+
+/// asks a yes or no question on `output`, reading the answer from `input`. see `prompt_yes_no`.
+///
+/// # examples.
+///
+/// ```
+/// # use ari::io::stdin;
+///
+/// let mut output = vec![];
+/// let answer = stdin::prompt_yes_no_with(&mut &b"maybe\nYES\n"[..], &mut output, "continue?", false).unwrap();
+///
+/// assert_eq!(answer, true);
+/// assert_eq!(String::from_utf8(output).unwrap(), "continue? [y/N] continue? [y/N] ");
+/// assert_eq!(stdin::prompt_yes_no_with(&mut &b"\n"[..], &mut vec![], "continue?", true).unwrap(), true);
+/// ```
+pub fn prompt_yes_no_with(
+    input: &mut impl BufRead,
+    output: &mut impl Write,
+    message: &str,
+    default: bool,
+) -> Result<bool, std::io::Error> {
+    let hint = if default { "[Y/n]" } else { "[y/N]" };
+
+    loop {
+        let answer = match prompt_line_with(input, output, &format!("{} {} ", message, hint)) {
+            Ok(answer) => answer,
+            Err(error) if error.kind() == ErrorKind::UnexpectedEof => return Ok(default),
+            Err(error) => return Err(error),
+        };
+
+        match answer.trim().to_ascii_lowercase().as_str() {
+            "" => return Ok(default),
+            "y" | "yes" => return Ok(true),
+            "n" | "no" => return Ok(false),
+            _ => continue,
+        }
+    }
+}
+
+/// lists `options` on stderr and asks the user to pick one, returning its index.
+///
+/// an option may be picked by its number, or by its text (case insensitively). any other answer asks the question
+/// again. fails with `ErrorKind::UnexpectedEof` if stdin is closed first, or `ErrorKind::InvalidInput` if `options` is
+/// empty.
+pub fn prompt_choice(message: &str, options: &[&str]) -> Result<usize, std::io::Error> {
+    prompt_choice_with(
+        &mut std::io::stdin().lock(),
+        &mut std::io::stderr(),
+        message,
+        options,
+    )
+}
+
+/// lists `options` on `output` and reads the user's pick from `input`. see `prompt_choice`.
+///
+/// # examples.
+///
+/// ```
+/// # use ari::io::stdin;
+///
+/// let options = ["debug", "release"];
+///
+/// assert_eq!(stdin::prompt_choice_with(&mut &b"2\n"[..], &mut vec![], "profile:", &options).unwrap(), 1);
+/// assert_eq!(stdin::prompt_choice_with(&mut &b"3\nDebug\n"[..], &mut vec![], "profile:", &options).unwrap(), 0);
+/// ```
+pub fn prompt_choice_with(
+    input: &mut impl BufRead,
+    output: &mut impl Write,
+    message: &str,
+    options: &[&str],
+) -> Result<usize, std::io::Error> {
+    if options.is_empty() {
+        return Err(std::io::Error::new(
+            ErrorKind::InvalidInput,
+            "no options to choose from",
+        ));
+    }
+
+    writeln!(output, "{}", message)?;
+
+    for (i, option) in options.iter().enumerate() {
+        writeln!(output, "  {}) {}", i + 1, option)?;
+    }
+
+    loop {
+        let answer = prompt_line_with(input, output, &format!("[1-{}]: ", options.len()))?;
+        let answer = answer.trim();
+
+        if let Ok(number) = answer.parse::<usize>() {
+            if (1..=options.len()).contains(&number) {
+                return Ok(number - 1);
+            }
+        }
+
+        if let Some(i) = options.iter().position(|x| x.eq_ignore_ascii_case(answer)) {
+            return Ok(i);
+        }
+    }
+}
+
+/// writes `message` to stderr, then reads a line from stdin without echoing it.
+///
+/// echo is restored once the line is read, even if reading fails or panics. when stdin is not a terminal, the line is
+/// read as-is, so that passwords may be piped in.
+pub fn prompt_password(message: &str) -> Result<String, std::io::Error> {
+    let mut output = std::io::stderr();
+    let input = std::io::stdin();
+
+    write!(output, "{}", message)?;
+    output.flush()?;
+
+    if !input.is_terminal() {
+        return read_line(&mut input.lock());
+    }
+
+    let password = {
+        let _guard = crate::io::sys::disable_echo()?;
+
+        read_line(&mut input.lock())
+    };
+
+    // the console does not echo the line break on windows.
+    if cfg!(windows) {
+        writeln!(output)?;
+    }
+
+    password
+}
+
+// reads a line from `input`, stripping its line ending.
+fn read_line(input: &mut impl BufRead) -> Result<String, std::io::Error> {
+    let mut line = String::new();
+
+    if input.read_line(&mut line)? == 0 {
+        return Err(ErrorKind::UnexpectedEof.into());
+    }
+
+    if line.ends_with('\n') {
+        line.pop();
+
+        if line.ends_with('\r') {
+            line.pop();
+        }
+    }
+
+    Ok(line)
+}
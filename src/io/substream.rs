@@ -0,0 +1,199 @@
+use std::io::{ErrorKind, Read, Seek, SeekFrom};
+
+/// a bounded view over the byte range `[offset, offset + len)` of an underlying stream.
+///
+/// the view starts at position 0 and never reads outside of its range. the inner stream is repositioned before every
+/// read, so several views may share a single handle, such as `&File`, without interfering with each other.
+///
+/// sharing a handle is only safe on a single thread. repositioning and reading are separate calls, so views on
+/// different threads may move the shared position between them and read from the wrong offset. handles duplicated with
+/// `File::try_clone` share a position too, so views used from several threads must each open the file separately.
+///
+/// # examples.
+///
+/// ```
+/// # use ari::io::SubStream;
+/// # use std::io::{Cursor, Read, Seek, SeekFrom};
+///
+/// let mut stream = SubStream::new(Cursor::new(b"header:payload:trailer"), 7, 7);
+/// let mut payload = String::new();
+///
+/// stream.read_to_string(&mut payload).unwrap();
+/// assert_eq!(payload, "payload");
+///
+/// stream.seek(SeekFrom::End(-4)).unwrap();
+/// payload.clear();
+/// stream.read_to_string(&mut payload).unwrap();
+/// assert_eq!(payload, "load");
+/// ```
+#[derive(Clone, Debug)]
+pub struct SubStream<R> {
+    inner: R,
+    offset: u64,
+    len: u64,
+    position: u64,
+}
+
+impl<R> SubStream<R> {
+    /// creates a view over `len` bytes of `inner`, starting at `offset`.
+    pub fn new(inner: R, offset: u64, len: u64) -> SubStream<R> {
+        SubStream {
+            inner,
+            offset,
+            len: len.min(u64::MAX - offset),
+            position: 0,
+        }
+    }
+
+    /// creates a view for each `(offset, len)` range in `ranges`, each over a clone of `inner`. this is intended for
+    /// handles that share the underlying stream, such as `&File`, and the views must then be used from a single thread.
+    ///
+    /// fails with `ErrorKind::InvalidInput` if any of the ranges overlap. empty ranges never overlap.
+    ///
+    /// # examples.
+    ///
+    /// ```
+    /// # use ari::io::SubStream;
+    /// # use std::io::{Cursor, Read};
+    ///
+    /// let data = b"firstsecond";
+    /// let streams = SubStream::split(Cursor::new(&data[..]), vec![(0, 5), (5, 6)]).unwrap();
+    /// let parts = streams
+    ///     .into_iter()
+    ///     .map(|mut x| {
+    ///         let mut part = String::new();
+    ///
+    ///         x.read_to_string(&mut part).unwrap();
+    ///         part
+    ///     })
+    ///     .collect::<Vec<_>>();
+    ///
+    /// assert_eq!(parts, vec!["first", "second"]);
+    /// assert!(SubStream::split(Cursor::new(&data[..]), vec![(0, 5), (4, 6)]).is_err());
+    /// assert!(SubStream::split(Cursor::new(&data[..]), vec![(0, 5), (2, 0)]).is_ok());
+    /// ```
+    pub fn split(
+        inner: R,
+        ranges: impl IntoIterator<Item = (u64, u64)>,
+    ) -> Result<Vec<SubStream<R>>, std::io::Error>
+    where
+        R: Clone,
+    {
+        SubStream::split_with(ranges, || Ok(inner.clone()))
+    }
+
+    /// creates a view for each `(offset, len)` range in `ranges`, each over a handle returned by `open`. views may be
+    /// used from different threads only if `open` returns independent handles, such as by calling `File::open`.
+    ///
+    /// fails with `ErrorKind::InvalidInput` if any of the ranges overlap. empty ranges never overlap.
+    pub fn split_with(
+        ranges: impl IntoIterator<Item = (u64, u64)>,
+        mut open: impl FnMut() -> Result<R, std::io::Error>,
+    ) -> Result<Vec<SubStream<R>>, std::io::Error> {
+        let ranges = ranges.into_iter().collect::<Vec<_>>();
+
+        // an empty range contains no bytes, so it cannot overlap anything.
+        let mut sorted = ranges
+            .iter()
+            .copied()
+            .filter(|x| x.1 > 0)
+            .collect::<Vec<_>>();
+
+        sorted.sort_unstable();
+
+        for pair in sorted.windows(2) {
+            if pair[0].0.saturating_add(pair[0].1) > pair[1].0 {
+                return Err(std::io::Error::new(
+                    ErrorKind::InvalidInput,
+                    "sub-stream ranges overlap",
+                ));
+            }
+        }
+
+        ranges
+            .into_iter()
+            .map(|(offset, len)| Ok(SubStream::new(open()?, offset, len)))
+            .collect()
+    }
+
+    /// returns the offset of this view within the inner stream.
+    pub fn offset(&self) -> u64 {
+        self.offset
+    }
+
+    /// returns the length of this view in bytes.
+    pub fn len(&self) -> u64 {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn get_ref(&self) -> &R {
+        &self.inner
+    }
+
+    /// returns a mutable reference to the inner stream. the inner stream is repositioned on the next read, so moving it
+    /// does not affect this view.
+    pub fn get_mut(&mut self) -> &mut R {
+        &mut self.inner
+    }
+
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+}
+
+impl<R> Read for SubStream<R>
+where
+    R: Read + Seek,
+{
+    fn read(&mut self, buffer: &mut [u8]) -> Result<usize, std::io::Error> {
+        let remaining = self.len.saturating_sub(self.position);
+        let length = (buffer.len() as u64).min(remaining) as usize;
+
+        if length == 0 {
+            return Ok(0);
+        }
+
+        self.inner
+            .seek(SeekFrom::Start(self.offset + self.position))?;
+
+        let read = self.inner.read(&mut buffer[..length])?;
+
+        self.position += read as u64;
+        Ok(read)
+    }
+}
+
+impl<R> Seek for SubStream<R> {
+    /// seeks within this view. `SeekFrom::End` is relative to the end of the view. seeking past the end is allowed, and
+    /// subsequent reads return no data.
+    fn seek(&mut self, position: SeekFrom) -> Result<u64, std::io::Error> {
+        let (base, delta) = match position {
+            SeekFrom::Start(x) => {
+                self.position = x;
+                return Ok(x);
+            }
+            SeekFrom::Current(x) => (self.position, x),
+            SeekFrom::End(x) => (self.len, x),
+        };
+
+        let position = match delta >= 0 {
+            true => base.checked_add(delta as u64),
+            false => base.checked_sub(delta.wrapping_neg() as u64),
+        };
+
+        match position {
+            Some(x) => {
+                self.position = x;
+                Ok(x)
+            }
+            None => Err(std::io::Error::new(
+                ErrorKind::InvalidInput,
+                "invalid seek to a negative or overflowing position",
+            )),
+        }
+    }
+}
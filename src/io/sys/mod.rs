@@ -0,0 +1,9 @@
+#[cfg(windows)]
+#[path = "win.rs"]
+mod a;
+
+#[cfg(unix)]
+#[path = "unix.rs"]
+mod a;
+
+pub(crate) use self::a::*;
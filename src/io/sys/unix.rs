@@ -0,0 +1,38 @@
+use std::mem::MaybeUninit;
+
+/// restores the terminal attributes of stdin when dropped.
+pub(crate) struct EchoGuard {
+    original: libc::termios,
+}
+
+impl Drop for EchoGuard {
+    fn drop(&mut self) {
+        unsafe {
+            libc::tcsetattr(libc::STDIN_FILENO, libc::TCSANOW, &self.original);
+        }
+    }
+}
+
+/// disables echoing of characters typed into stdin, until the returned guard is dropped. stdin must be a terminal.
+pub(crate) fn disable_echo() -> Result<EchoGuard, std::io::Error> {
+    unsafe {
+        let mut attributes = MaybeUninit::<libc::termios>::uninit();
+
+        if libc::tcgetattr(libc::STDIN_FILENO, attributes.as_mut_ptr()) != 0 {
+            return Err(std::io::Error::last_os_error());
+        }
+
+        let original = attributes.assume_init();
+        let mut silent = original;
+
+        // keep `ECHONL`, so that the user still sees the line break when they press enter.
+        silent.c_lflag &= !(libc::ECHO | libc::ECHOE | libc::ECHOK);
+        silent.c_lflag |= libc::ECHONL;
+
+        if libc::tcsetattr(libc::STDIN_FILENO, libc::TCSANOW, &silent) != 0 {
+            return Err(std::io::Error::last_os_error());
+        }
+
+        Ok(EchoGuard { original })
+    }
+}
@@ -0,0 +1,36 @@
+use winapi::shared::minwindef::{DWORD, TRUE};
+use winapi::um::consoleapi::{GetConsoleMode, SetConsoleMode};
+use winapi::um::processenv::GetStdHandle;
+use winapi::um::winbase::STD_INPUT_HANDLE;
+use winapi::um::wincon::ENABLE_ECHO_INPUT;
+use winapi::um::winnt::HANDLE;
+
+/// restores the console mode of stdin when dropped.
+pub(crate) struct EchoGuard {
+    handle: HANDLE,
+    original: DWORD,
+}
+
+impl Drop for EchoGuard {
+    fn drop(&mut self) {
+        unsafe {
+            SetConsoleMode(self.handle, self.original);
+        }
+    }
+}
+
+/// disables echoing of characters typed into stdin, until the returned guard is dropped. stdin must be a console.
+pub(crate) fn disable_echo() -> Result<EchoGuard, std::io::Error> {
+    unsafe {
+        let handle = GetStdHandle(STD_INPUT_HANDLE);
+        let mut original: DWORD = 0;
+
+        if GetConsoleMode(handle, &mut original) != TRUE
+            || SetConsoleMode(handle, original & !ENABLE_ECHO_INPUT) != TRUE
+        {
+            return Err(std::io::Error::last_os_error());
+        }
+
+        Ok(EchoGuard { handle, original })
+    }
+}
@@ -0,0 +1,256 @@
+// well-known directories. on unix, these follow the xdg base directory specification, including its `XDG_*`
+// overrides. on windows, they are derived from `%APPDATA%`, `%LOCALAPPDATA%` and the user's profile.
+//
+// https://specifications.freedesktop.org/basedir-spec/latest/
+
+use std::ffi::OsString;
+use std::path::{Path, PathBuf};
+
+/// a base directory kind, for use with `app_dir`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum BaseDirectory {
+    Config,
+    Cache,
+    Data,
+    State,
+    Runtime,
+}
+
+/// a user directory kind, as listed in `user-dirs.dirs`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum UserDirectory {
+    Desktop,
+    Documents,
+    Downloads,
+    Music,
+    Pictures,
+    PublicShare,
+    Templates,
+    Videos,
+}
+
+impl UserDirectory {
+    // returns the `user-dirs.dirs` key and conventional folder name for this directory.
+    fn names(self) -> (&'static str, &'static str) {
+        match self {
+            UserDirectory::Desktop => ("XDG_DESKTOP_DIR", "Desktop"),
+            UserDirectory::Documents => ("XDG_DOCUMENTS_DIR", "Documents"),
+            UserDirectory::Downloads => ("XDG_DOWNLOAD_DIR", "Downloads"),
+            UserDirectory::Music => ("XDG_MUSIC_DIR", "Music"),
+            UserDirectory::Pictures => ("XDG_PICTURES_DIR", "Pictures"),
+            UserDirectory::PublicShare => ("XDG_PUBLICSHARE_DIR", "Public"),
+            UserDirectory::Templates => ("XDG_TEMPLATES_DIR", "Templates"),
+            UserDirectory::Videos => ("XDG_VIDEOS_DIR", "Videos"),
+        }
+    }
+}
+
+/// returns the home directory of the current user.
+pub fn home_dir() -> Option<PathBuf> {
+    crate::path::sys::home_directory()
+}
+
+/// returns the user's configuration directory: `$XDG_CONFIG_HOME` or `~/.config` on unix, `%APPDATA%` on windows.
+pub fn config_dir() -> Option<PathBuf> {
+    base_directory("XDG_CONFIG_HOME", ".config", "APPDATA")
+}
+
+/// returns the user's cache directory: `$XDG_CACHE_HOME` or `~/.cache` on unix, `%LOCALAPPDATA%` on windows.
+pub fn cache_dir() -> Option<PathBuf> {
+    base_directory("XDG_CACHE_HOME", ".cache", "LOCALAPPDATA")
+}
+
+/// returns the user's data directory: `$XDG_DATA_HOME` or `~/.local/share` on unix, `%APPDATA%` on windows.
+pub fn data_dir() -> Option<PathBuf> {
+    base_directory("XDG_DATA_HOME", ".local/share", "APPDATA")
+}
+
+/// returns the user's state directory: `$XDG_STATE_HOME` or `~/.local/state` on unix, `%LOCALAPPDATA%` on windows.
+pub fn state_dir() -> Option<PathBuf> {
+    base_directory("XDG_STATE_HOME", ".local/state", "LOCALAPPDATA")
+}
+
+/// returns the user's runtime directory: `$XDG_RUNTIME_DIR` on unix. the specification defines no fallback, so this is
+/// `None` when the variable is unset, and always `None` on windows.
+pub fn runtime_dir() -> Option<PathBuf> {
+    match cfg!(windows) {
+        true => None,
+        false => absolute_env("XDG_RUNTIME_DIR"),
+    }
+}
+
+/// returns the preference ordered list of configuration directories: `config_dir()`, followed by `$XDG_CONFIG_DIRS`
+/// (default `/etc/xdg`) on unix.
+pub fn config_dirs() -> Vec<PathBuf> {
+    search_directories(config_dir(), "XDG_CONFIG_DIRS", "/etc/xdg")
+}
+
+/// returns the preference ordered list of data directories: `data_dir()`, followed by `$XDG_DATA_DIRS` (default
+/// `/usr/local/share:/usr/share`) on unix.
+pub fn data_dirs() -> Vec<PathBuf> {
+    search_directories(data_dir(), "XDG_DATA_DIRS", "/usr/local/share:/usr/share")
+}
+
+/// returns the first existing file named `relative` within `config_dirs()`.
+pub fn find_config_file(relative: impl AsRef<Path>) -> Option<PathBuf> {
+    find_file(config_dirs(), relative.as_ref())
+}
+
+/// returns the first existing file named `relative` within `data_dirs()`.
+pub fn find_data_file(relative: impl AsRef<Path>) -> Option<PathBuf> {
+    find_file(data_dirs(), relative.as_ref())
+}
+
+/// returns a user directory such as `Downloads` or `Documents`.
+///
+/// on unix, this reads `user-dirs.dirs` from `config_dir()`. a directory that is missing from the file, or disabled by
+/// being set to the home directory itself, yields `None`. if the file does not exist, the conventional folder within the
+/// home directory is returned. on windows, the conventional folder within the user's profile is returned.
+pub fn user_dir(directory: UserDirectory) -> Option<PathBuf> {
+    let home = home_dir()?;
+    let (key, name) = directory.names();
+
+    if cfg!(windows) {
+        return Some(home.join(name));
+    }
+
+    let file = config_dir()?.join("user-dirs.dirs");
+
+    match crate::fs::read_all_text(file) {
+        Ok(text) => parse_user_dirs(&text, &home, key).filter(|x| *x != home),
+        Err(_) => Some(home.join(name)),
+    }
+}
+
+pub fn desktop_dir() -> Option<PathBuf> {
+    user_dir(UserDirectory::Desktop)
+}
+
+pub fn documents_dir() -> Option<PathBuf> {
+    user_dir(UserDirectory::Documents)
+}
+
+pub fn downloads_dir() -> Option<PathBuf> {
+    user_dir(UserDirectory::Downloads)
+}
+
+pub fn music_dir() -> Option<PathBuf> {
+    user_dir(UserDirectory::Music)
+}
+
+pub fn pictures_dir() -> Option<PathBuf> {
+    user_dir(UserDirectory::Pictures)
+}
+
+pub fn public_share_dir() -> Option<PathBuf> {
+    user_dir(UserDirectory::PublicShare)
+}
+
+pub fn templates_dir() -> Option<PathBuf> {
+    user_dir(UserDirectory::Templates)
+}
+
+pub fn videos_dir() -> Option<PathBuf> {
+    user_dir(UserDirectory::Videos)
+}
+
+/// returns the `app` scoped directory within a base directory, creating it if it does not already exist.
+///
+/// on unix, any directories created are only accessible by the current user (`0700`), as required by the
+/// specification.
+///
+/// # examples.
+///
+/// ```
+/// # use ari::path::dirs::{self, BaseDirectory};
+///
+/// std::env::set_var("XDG_CACHE_HOME", std::env::temp_dir().join("ari-dirs-example"));
+///
+/// let directory = dirs::app_dir(BaseDirectory::Cache, "ari").unwrap();
+///
+/// assert!(directory.ends_with("ari-dirs-example/ari"));
+/// assert!(ari::fs::directory_exists(&directory));
+/// ```
+pub fn app_dir(base: BaseDirectory, app: &str) -> Result<PathBuf, std::io::Error> {
+    let base = match base {
+        BaseDirectory::Config => config_dir(),
+        BaseDirectory::Cache => cache_dir(),
+        BaseDirectory::Data => data_dir(),
+        BaseDirectory::State => state_dir(),
+        BaseDirectory::Runtime => runtime_dir(),
+    };
+
+    let directory = base.ok_or(std::io::ErrorKind::NotFound)?.join(app);
+
+    crate::path::sys::create_private_directory(&directory)?;
+
+    Ok(directory)
+}
+
+// returns `$variable` on unix if it is absolute, otherwise `~/fallback`. on windows, returns `%windows%`.
+fn base_directory(variable: &str, fallback: &str, windows: &str) -> Option<PathBuf> {
+    match cfg!(windows) {
+        true => absolute_env(windows),
+        false => absolute_env(variable).or_else(|| home_dir().map(|x| x.join(fallback))),
+    }
+}
+
+// returns the value of an environment variable if it is an absolute path. the specification requires relative paths
+// to be ignored.
+fn absolute_env(variable: &str) -> Option<PathBuf> {
+    std::env::var_os(variable)
+        .map(PathBuf::from)
+        .filter(|x| x.is_absolute())
+}
+
+fn search_directories(first: Option<PathBuf>, variable: &str, fallback: &str) -> Vec<PathBuf> {
+    let mut directories = first.into_iter().collect::<Vec<_>>();
+
+    if !cfg!(windows) {
+        let value = std::env::var_os(variable)
+            .filter(|x| !x.is_empty())
+            .unwrap_or_else(|| OsString::from(fallback));
+
+        directories.extend(std::env::split_paths(&value).filter(|x| x.is_absolute()));
+    }
+
+    directories
+}
+
+fn find_file(directories: Vec<PathBuf>, relative: &Path) -> Option<PathBuf> {
+    directories
+        .into_iter()
+        .map(|x| x.join(relative))
+        .find(|x| crate::fs::file_exists(x))
+}
+
+// parses the value for `key` out of a `user-dirs.dirs` file. values are either `"$HOME/relative"` or
+// `"/absolute"`.
+fn parse_user_dirs(text: &str, home: &Path, key: &str) -> Option<PathBuf> {
+    for line in text.lines().map(|x| x.trim()) {
+        if line.starts_with('#') {
+            continue;
+        }
+
+        let (name, value) = match line.find('=') {
+            Some(i) => (line[..i].trim(), line[i + 1..].trim()),
+            None => continue,
+        };
+
+        if name != key {
+            continue;
+        }
+
+        let value = value.trim_matches('"').replace("\\\"", "\"");
+
+        return match value.strip_prefix("$HOME") {
+            Some("") => Some(home.to_path_buf()),
+            Some(rest) if rest.starts_with('/') => Some(home.join(&rest[1..])),
+            Some(_) => None,
+            None if value.starts_with('/') => Some(PathBuf::from(value)),
+            None => None,
+        };
+    }
+
+    None
+}
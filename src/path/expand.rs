@@ -0,0 +1,214 @@
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+use std::path::PathBuf;
+
+/// an error produced when expanding a path.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum ExpandError {
+    /// a variable was referenced without a default value, but it is not defined.
+    UndefinedVariable(String),
+
+    /// `~` was used, but the current user's home directory could not be determined.
+    NoHomeDirectory,
+
+    /// `~user` was used, but the home directory of `user` could not be determined.
+    UnknownUser(String),
+
+    /// a `${` at this byte offset was never closed.
+    Unterminated(usize),
+}
+
+impl Display for ExpandError {
+    fn fmt(&self, formatter: &mut Formatter) -> Result<(), std::fmt::Error> {
+        match self {
+            ExpandError::UndefinedVariable(name) => {
+                write!(formatter, "undefined variable `{}`", name)
+            }
+            ExpandError::NoHomeDirectory => {
+                write!(formatter, "unable to determine the home directory")
+            }
+            ExpandError::UnknownUser(user) => write!(formatter, "unknown user `{}`", user),
+            ExpandError::Unterminated(offset) => {
+                write!(formatter, "unterminated `${{` at offset {}", offset)
+            }
+        }
+    }
+}
+
+impl Error for ExpandError {}
+
+impl From<ExpandError> for std::io::Error {
+    fn from(error: ExpandError) -> std::io::Error {
+        std::io::Error::new(std::io::ErrorKind::InvalidInput, error)
+    }
+}
+
+/// expands `~`, `~user`, `$VAR`, `${VAR}` and `${VAR:-default}` in `path`, using the process environment.
+///
+/// - `~` and `~user` are only expanded at the start of `path`, and only when followed by a separator or the end.
+/// - variable names consist of ascii letters, digits and underscores.
+/// - `${VAR:-default}` expands to `default` if `VAR` is undefined or empty. `default` may itself contain variables.
+/// - `$$` expands to a literal `$`, as does a `$` that is not followed by a variable name.
+///
+/// referencing an undefined variable without a default is an error.
+///
+/// # examples.
+///
+/// ```
+/// # use ari::path;
+/// # use std::path::PathBuf;
+///
+/// std::env::set_var("ARI_EXPAND_CACHE", "/var/cache");
+///
+/// let expanded = path::expand("${ARI_EXPAND_CACHE}/ari/${ARI_EXPAND_TARGET:-release}").unwrap();
+///
+/// assert_eq!(expanded, PathBuf::from("/var/cache/ari/release"));
+/// assert!(path::expand("$ARI_EXPAND_UNDEFINED/ari").is_err());
+/// ```
+pub fn expand(path: &str) -> Result<PathBuf, ExpandError> {
+    expand_with(path, |name| std::env::var(name).ok())
+}
+
+/// expands `path` like `expand`, looking up variables with `lookup` instead of the process environment.
+///
+/// # examples.
+///
+/// ```
+/// # use ari::path;
+/// # use std::path::PathBuf;
+///
+/// let lookup = |name: &str| match name {
+///     "TARGET" => Some("x86_64".to_owned()),
+///     _ => None,
+/// };
+///
+/// assert_eq!(path::expand_with("out/$TARGET/$$1", lookup).unwrap(), PathBuf::from("out/x86_64/$1"));
+/// assert_eq!(path::expand_with("${CACHE:-/tmp}/${TARGET}", lookup).unwrap(), PathBuf::from("/tmp/x86_64"));
+/// ```
+pub fn expand_with(
+    path: &str,
+    lookup: impl Fn(&str) -> Option<String>,
+) -> Result<PathBuf, ExpandError> {
+    let mut expanded = String::with_capacity(path.len());
+    let rest = expand_tilde(path, &mut expanded)?;
+    let offset = path.len() - rest.len();
+
+    expand_variables(rest, offset, &lookup, &mut expanded)?;
+
+    Ok(PathBuf::from(expanded))
+}
+
+// expands a leading `~` or `~user` into `into`, returning the remainder of `path`.
+fn expand_tilde<'a>(path: &'a str, into: &mut String) -> Result<&'a str, ExpandError> {
+    let rest = match path.strip_prefix('~') {
+        Some(x) => x,
+        None => return Ok(path),
+    };
+
+    let end = rest.find(&['/', '\\'][..]).unwrap_or(rest.len());
+    let user = &rest[..end];
+    let home = match user {
+        "" => crate::path::dirs::home_dir().ok_or(ExpandError::NoHomeDirectory)?,
+        user => crate::path::sys::user_home_directory(user)
+            .ok_or_else(|| ExpandError::UnknownUser(user.to_owned()))?,
+    };
+
+    into.push_str(&home.to_string_lossy());
+
+    Ok(&rest[end..])
+}
+
+// expands all variables in `path` into `into`. `offset` is the position of `path` in the original string, used for
+// error reporting.
+fn expand_variables(
+    path: &str,
+    offset: usize,
+    lookup: &dyn Fn(&str) -> Option<String>,
+    into: &mut String,
+) -> Result<(), ExpandError> {
+    let is_name = |x: u8| x.is_ascii_alphanumeric() || x == b'_';
+    let bytes = path.as_bytes();
+    let mut i = 0;
+
+    while let Some(position) = path[i..].find('$') {
+        let start = i + position;
+
+        into.push_str(&path[i..start]);
+
+        match bytes.get(start + 1) {
+            Some(b'$') => {
+                into.push('$');
+                i = start + 2;
+            }
+
+            Some(b'{') => {
+                let end = matching_brace(path, start + 2)
+                    .ok_or(ExpandError::Unterminated(offset + start))?;
+                let body = &path[start + 2..end];
+                let (name, default) = match body.find(":-") {
+                    Some(x) => (&body[..x], Some(&body[x + 2..])),
+                    None => (body, None),
+                };
+
+                match (lookup(name), default) {
+                    (Some(value), Some(_)) if !value.is_empty() => into.push_str(&value),
+                    (Some(value), None) => into.push_str(&value),
+                    (_, Some(default)) => expand_variables(
+                        default,
+                        offset + start + 2 + name.len() + 2,
+                        lookup,
+                        into,
+                    )?,
+                    (None, None) => return Err(ExpandError::UndefinedVariable(name.to_owned())),
+                }
+
+                i = end + 1;
+            }
+
+            Some(x) if is_name(*x) => {
+                let length = bytes[start + 1..]
+                    .iter()
+                    .take_while(|x| is_name(**x))
+                    .count();
+                let name = &path[start + 1..start + 1 + length];
+                let value =
+                    lookup(name).ok_or_else(|| ExpandError::UndefinedVariable(name.to_owned()))?;
+
+                into.push_str(&value);
+                i = start + 1 + length;
+            }
+
+            _ => {
+                into.push('$');
+                i = start + 1;
+            }
+        }
+    }
+
+    into.push_str(&path[i..]);
+
+    Ok(())
+}
+
+// returns the position of the `}` which closes a `${` whose body begins at `start`, accounting for nested `${...}`.
+fn matching_brace(path: &str, start: usize) -> Option<usize> {
+    let bytes = path.as_bytes();
+    let mut depth = 0;
+    let mut i = start;
+
+    while i < bytes.len() {
+        match bytes[i] {
+            b'$' if bytes.get(i + 1) == Some(&b'{') => {
+                depth += 1;
+                i += 1;
+            }
+            b'}' if depth == 0 => return Some(i),
+            b'}' => depth -= 1,
+            _ => {}
+        }
+
+        i += 1;
+    }
+
+    None
+}
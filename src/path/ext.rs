@@ -1,5 +1,115 @@
+use std::ffi::{OsStr, OsString};
 use std::path::{Path, PathBuf};
 
+use crate::path::util::{os_str_as_u8_slice, u8_slice_as_os_str};
+
+/// extension methods for compound extensions, such as `archive.tar.gz`.
+///
+/// unlike `std`, every dot-separated part of a file name after the stem is considered an extension. leading dots belong
+/// to the stem, so `.bashrc` has no extensions and `.config.json` has the stem `.config`.
+pub trait PathExt {
+    /// returns every extension of the file name, in order.
+    ///
+    /// # examples
+    ///
+    /// ```
+    /// # use ari::path::PathExt;
+    /// # use std::path::Path;
+    ///
+    /// assert_eq!(Path::new("/var/archive.tar.gz").extensions(), vec!["tar", "gz"]);
+    /// assert_eq!(Path::new("/home/.config.json").extensions(), vec!["json"]);
+    /// assert_eq!(Path::new("/home/.bashrc").extensions().is_empty(), true);
+    /// ```
+    fn extensions(&self) -> Vec<&OsStr>;
+
+    /// returns all extensions of the file name as a single string, without the leading dot.
+    ///
+    /// # examples
+    ///
+    /// ```
+    /// # use ari::path::PathExt;
+    /// # use std::ffi::OsStr;
+    /// # use std::path::Path;
+    ///
+    /// assert_eq!(Path::new("archive.tar.gz").full_extension(), Some(OsStr::new("tar.gz")));
+    /// assert_eq!(Path::new(".bashrc").full_extension(), None);
+    /// ```
+    fn full_extension(&self) -> Option<&OsStr>;
+
+    /// returns the file name without any of its extensions.
+    ///
+    /// # examples
+    ///
+    /// ```
+    /// # use ari::path::PathExt;
+    /// # use std::ffi::OsStr;
+    /// # use std::path::Path;
+    ///
+    /// assert_eq!(Path::new("archive.tar.gz").stem_without_all_extensions(), Some(OsStr::new("archive")));
+    /// assert_eq!(Path::new(".config.json").stem_without_all_extensions(), Some(OsStr::new(".config")));
+    /// ```
+    fn stem_without_all_extensions(&self) -> Option<&OsStr>;
+
+    /// returns a copy of this path with `suffix` inserted between the stem and extensions of its file name. if this path
+    /// has no file name, it is returned unchanged.
+    ///
+    /// # examples
+    ///
+    /// ```
+    /// # use ari::path::PathExt;
+    /// # use std::path::{Path, PathBuf};
+    ///
+    /// assert_eq!(Path::new("/out/ari.tar.gz").with_suffix_before_extension("-debug"), PathBuf::from("/out/ari-debug.tar.gz"));
+    /// assert_eq!(Path::new("/home/.bashrc").with_suffix_before_extension("-old"), PathBuf::from("/home/.bashrc-old"));
+    /// ```
+    fn with_suffix_before_extension(&self, suffix: impl AsRef<OsStr>) -> PathBuf;
+}
+
+impl PathExt for Path {
+    fn extensions(&self) -> Vec<&OsStr> {
+        match self.full_extension() {
+            Some(extension) => os_str_as_u8_slice(extension)
+                .split(|x| *x == b'.')
+                .map(|x| unsafe { u8_slice_as_os_str(x) })
+                .collect(),
+            None => vec![],
+        }
+    }
+
+    fn full_extension(&self) -> Option<&OsStr> {
+        let name = os_str_as_u8_slice(self.file_name()?);
+        let dot = first_extension_dot(name)?;
+
+        Some(unsafe { u8_slice_as_os_str(&name[dot + 1..]) })
+    }
+
+    fn stem_without_all_extensions(&self) -> Option<&OsStr> {
+        let name = os_str_as_u8_slice(self.file_name()?);
+        let dot = first_extension_dot(name).unwrap_or(name.len());
+
+        Some(unsafe { u8_slice_as_os_str(&name[..dot]) })
+    }
+
+    fn with_suffix_before_extension(&self, suffix: impl AsRef<OsStr>) -> PathBuf {
+        let mut path = self.to_path_buf();
+
+        if let Some(stem) = self.stem_without_all_extensions() {
+            let mut name = OsString::from(stem);
+
+            name.push(suffix);
+
+            if let Some(extension) = self.full_extension() {
+                name.push(".");
+                name.push(extension);
+            }
+
+            path.set_file_name(name);
+        }
+
+        path
+    }
+}
+
 pub trait PathBufExt {
     /// appends a `path` to self. always treats `path` as a relative path.
     ///
@@ -16,10 +126,57 @@ pub trait PathBufExt {
     /// assert_eq!(path, expected);
     /// ```
     fn append(&mut self, path: impl AsRef<Path>);
+
+    /// replaces all extensions of the file name with `extension`, or removes them if `extension` is empty. returns
+    /// false and does nothing if this path has no file name.
+    ///
+    /// # examples
+    ///
+    /// ```
+    /// # use ari::path::PathBufExt;
+    /// # use std::path::PathBuf;
+    ///
+    /// let mut path = PathBuf::from("/var/ari.tar.gz");
+    /// path.set_full_extension("zip");
+    ///
+    /// assert_eq!(path, PathBuf::from("/var/ari.zip"));
+    ///
+    /// path.set_full_extension("");
+    ///
+    /// assert_eq!(path, PathBuf::from("/var/ari"));
+    /// ```
+    fn set_full_extension(&mut self, extension: impl AsRef<OsStr>) -> bool;
 }
 
 impl PathBufExt for PathBuf {
     fn append(&mut self, path: impl AsRef<Path>) {
         crate::path::util::_ari_path_append(self, path);
     }
+
+    fn set_full_extension(&mut self, extension: impl AsRef<OsStr>) -> bool {
+        let mut name = match self.stem_without_all_extensions() {
+            Some(stem) => OsString::from(stem),
+            None => return false,
+        };
+
+        let extension = extension.as_ref();
+
+        if !extension.is_empty() {
+            name.push(".");
+            name.push(extension);
+        }
+
+        self.set_file_name(name);
+        true
+    }
+}
+
+// returns the position of the dot that begins the extensions of a file name. leading dots are part of the stem.
+fn first_extension_dot(name: &[u8]) -> Option<usize> {
+    let leading = name.iter().take_while(|x| **x == b'.').count();
+
+    name[leading..]
+        .iter()
+        .position(|x| *x == b'.')
+        .map(|x| x + leading)
 }
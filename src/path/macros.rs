@@ -31,3 +31,34 @@ macro_rules! path {
         path
     });
 }
+
+/// creates a path from several components like `path!`, first expanding `~`, `~user` and environment variables in each
+/// component with `ari::path::expand`. returns a `Result<PathBuf, ari::path::ExpandError>`.
+///
+/// # examples.
+///
+/// ```
+/// # use ari::path_expand;
+/// # use std::path::PathBuf;
+///
+/// std::env::set_var("ARI_PATH_EXPAND_CACHE", "/var/cache");
+///
+/// let path = path_expand!["${ARI_PATH_EXPAND_CACHE}", "ari", "${ARI_PATH_EXPAND_TARGET:-release}"].unwrap();
+///
+/// assert_eq!(path, PathBuf::from("/var/cache/ari/release"));
+/// ```
+#[macro_export]
+macro_rules! path_expand {
+    ($initial: expr $(, $extra: expr)* $(,)*) => ({
+        (|| -> ::std::result::Result<::std::path::PathBuf, $crate::path::ExpandError> {
+            #[allow(unused_mut)]
+            let mut path = $crate::path::expand($initial)?;
+
+            $(
+                $crate::path::util::_ari_path_append(&mut path, $crate::path::expand($extra)?);
+            )*
+
+            Ok(path)
+        })()
+    });
+}
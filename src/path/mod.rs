@@ -1,8 +1,21 @@
+pub mod dirs;
 pub mod ext;
 pub mod macros;
 pub mod util;
 
+mod expand;
+mod posix;
+mod safe;
+mod sys;
+mod which;
+mod windows;
+
+pub use self::expand::*;
 pub use self::ext::*;
+pub use self::posix::*;
+pub use self::safe::*;
+pub use self::which::*;
+pub use self::windows::*;
 
 use std::borrow::Cow;
 use std::ffi::OsStr;
@@ -0,0 +1,259 @@
+use std::fmt::{Debug, Display, Formatter};
+use std::hash::{Hash, Hasher};
+
+use crate::path::WindowsPath;
+
+/// a single component of a `PosixPath`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum PosixComponent<'a> {
+    RootDir,
+    CurDir,
+    ParentDir,
+    Normal(&'a str),
+}
+
+/// a posix path, parsed and joined by posix rules regardless of the host platform.
+///
+/// only `/` is treated as a separator, `\` is an ordinary character. comparisons are case-sensitive and ignore redundant
+/// separators and `.` components.
+///
+/// # examples.
+///
+/// ```
+/// # use ari::path::{PosixComponent, PosixPath};
+///
+/// let path = PosixPath::new("/var/bin").join("ari/./hello.so");
+///
+/// assert_eq!(path.as_str(), "/var/bin/ari/./hello.so");
+/// assert_eq!(path, PosixPath::new("/var//bin/ari/hello.so"));
+/// assert_eq!(path.file_name(), Some("hello.so"));
+/// assert_eq!(path.parent(), Some(PosixPath::new("/var/bin/ari")));
+/// assert_eq!(path.join("/etc").as_str(), "/etc");
+///
+/// let relative = PosixPath::new(r"../a\b");
+/// let components = relative.components().collect::<Vec<_>>();
+///
+/// assert_eq!(components, vec![PosixComponent::ParentDir, PosixComponent::Normal(r"a\b")]);
+/// ```
+#[derive(Clone, Default)]
+pub struct PosixPath {
+    inner: String,
+}
+
+impl PosixPath {
+    pub fn new(path: impl Into<String>) -> PosixPath {
+        PosixPath { inner: path.into() }
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.inner
+    }
+
+    pub fn into_string(self) -> String {
+        self.inner
+    }
+
+    pub fn has_root(&self) -> bool {
+        self.inner.starts_with('/')
+    }
+
+    pub fn is_absolute(&self) -> bool {
+        self.has_root()
+    }
+
+    pub fn is_relative(&self) -> bool {
+        !self.has_root()
+    }
+
+    /// returns an iterator over the components of this path.
+    pub fn components(&self) -> impl Iterator<Item = PosixComponent<'_>> {
+        let root = match self.has_root() {
+            true => Some(PosixComponent::RootDir),
+            false => None,
+        };
+
+        root.into_iter().chain(self.body().map(|(_, x)| x))
+    }
+
+    /// returns the final component of this path, if it is a normal file or directory name.
+    pub fn file_name(&self) -> Option<&str> {
+        match self.body().last() {
+            Some((_, PosixComponent::Normal(x))) => Some(x),
+            _ => None,
+        }
+    }
+
+    /// returns this path without its final component, or `None` if it has no final component.
+    pub fn parent(&self) -> Option<PosixPath> {
+        let (offset, _) = self.body().last()?;
+        let minimum = self.has_root() as usize;
+        let trimmed = self.inner[minimum..offset].trim_end_matches('/');
+
+        Some(PosixPath::new(&self.inner[..minimum + trimmed.len()]))
+    }
+
+    /// extends this path with `path`. if `path` is absolute, it replaces this path.
+    pub fn push(&mut self, path: impl AsRef<str>) {
+        let path = path.as_ref();
+
+        if path.starts_with('/') {
+            self.inner.clear();
+        } else if !self.inner.is_empty() && !self.inner.ends_with('/') {
+            self.inner.push('/');
+        }
+
+        self.inner.push_str(path);
+    }
+
+    /// returns a new path with `path` pushed onto it. see `push`.
+    pub fn join(&self, path: impl AsRef<str>) -> PosixPath {
+        let mut joined = self.clone();
+
+        joined.push(path);
+        joined
+    }
+
+    /// appends a `path` to self. always treats `path` as a relative path, like `PathBufExt::append`.
+    pub fn append(&mut self, path: impl AsRef<str>) {
+        for bit in path.as_ref().split('/').filter(|x| !x.is_empty()) {
+            self.push(bit);
+        }
+    }
+
+    /// converts this path into a windows path. this is the inverse of `WindowsPath::to_posix`.
+    ///
+    /// a leading single letter directory becomes a disk prefix (`/c/x` => `C:\x`), and a leading double slash becomes a
+    /// unc prefix (`//server/share/x` => `\\server\share\x`). a double slash without both a server and a share is
+    /// treated as a single slash.
+    ///
+    /// # examples.
+    ///
+    /// ```
+    /// # use ari::path::PosixPath;
+    ///
+    /// assert_eq!(PosixPath::new("/c/var/bin").to_windows().as_str(), r"C:\var\bin");
+    /// assert_eq!(PosixPath::new("//server/share/bin").to_windows().as_str(), r"\\server\share\bin");
+    /// assert_eq!(PosixPath::new("/var/bin").to_windows().as_str(), r"\var\bin");
+    /// assert_eq!(PosixPath::new("//").to_windows().as_str(), r"\");
+    /// assert_eq!(PosixPath::new("//server").to_windows().as_str(), r"\server");
+    /// assert_eq!(PosixPath::new("//server//bin").to_windows().as_str(), r"\server\bin");
+    /// assert_eq!(PosixPath::new("ari/hello.so").to_windows().as_str(), r"ari\hello.so");
+    /// ```
+    pub fn to_windows(&self) -> WindowsPath {
+        let mut path = String::with_capacity(self.inner.len() + 1);
+        let mut start = 0;
+
+        // `.` and `?` are meaningful as a server name, so take the server and share verbatim.
+        let mut bits = self.inner.strip_prefix("//").unwrap_or("").splitn(3, '/');
+        let server = bits.next().unwrap_or("");
+        let share = bits.next().unwrap_or("");
+
+        // without both a server and a share there is no unc root, so the path is treated as a plain rooted path.
+        let unc = !server.is_empty() && !share.is_empty();
+
+        if unc {
+            path.push_str(&format!(r"\\{}\{}", server, share));
+            start = 2 + server.len() + 1 + share.len();
+        }
+
+        let mut components = self
+            .body()
+            .filter(|(offset, _)| *offset >= start)
+            .map(|(_, x)| x)
+            .peekable();
+
+        if !unc && self.has_root() {
+            match components.peek() {
+                Some(PosixComponent::Normal(x))
+                    if x.len() == 1 && x.as_bytes()[0].is_ascii_alphabetic() =>
+                {
+                    path.push_str(&format!("{}:", x.to_ascii_uppercase()));
+                    components.next();
+                }
+                _ => {}
+            }
+
+            path.push('\\');
+        }
+
+        for component in components {
+            if !path.is_empty() && !path.ends_with('\\') {
+                path.push('\\');
+            }
+
+            path.push_str(match component {
+                PosixComponent::RootDir => continue,
+                PosixComponent::CurDir => ".",
+                PosixComponent::ParentDir => "..",
+                PosixComponent::Normal(x) => x,
+            });
+        }
+
+        WindowsPath::new(path)
+    }
+
+    // returns the non-root components of this path, and the byte offset at which each of them begins.
+    fn body(&self) -> impl Iterator<Item = (usize, PosixComponent<'_>)> {
+        let mut offset = 0;
+
+        self.inner.split('/').enumerate().filter_map(move |(i, x)| {
+            let position = offset;
+
+            offset += x.len() + 1;
+
+            let component = match x {
+                "" => return None,
+                "." if i == 0 => PosixComponent::CurDir,
+                "." => return None,
+                ".." => PosixComponent::ParentDir,
+                x => PosixComponent::Normal(x),
+            };
+
+            Some((position, component))
+        })
+    }
+}
+
+impl Debug for PosixPath {
+    fn fmt(&self, formatter: &mut Formatter) -> Result<(), std::fmt::Error> {
+        Debug::fmt(&self.inner, formatter)
+    }
+}
+
+impl Display for PosixPath {
+    fn fmt(&self, formatter: &mut Formatter) -> Result<(), std::fmt::Error> {
+        Display::fmt(&self.inner, formatter)
+    }
+}
+
+impl PartialEq for PosixPath {
+    fn eq(&self, other: &PosixPath) -> bool {
+        self.components().eq(other.components())
+    }
+}
+
+impl Eq for PosixPath {}
+
+impl Hash for PosixPath {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.components().for_each(|x| x.hash(state));
+    }
+}
+
+impl AsRef<str> for PosixPath {
+    fn as_ref(&self) -> &str {
+        &self.inner
+    }
+}
+
+impl From<&str> for PosixPath {
+    fn from(path: &str) -> PosixPath {
+        PosixPath::new(path)
+    }
+}
+
+impl From<String> for PosixPath {
+    fn from(path: String) -> PosixPath {
+        PosixPath::new(path)
+    }
+}
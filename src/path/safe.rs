@@ -0,0 +1,263 @@
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+
+use crate::path::util::os_str_as_u8_slice;
+
+/// controls how `safe_join_with` treats symbolic links beneath the root.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum SymlinkOption {
+    /// symbolic links are not inspected. only the lexical path is validated.
+    Ignore,
+
+    /// every existing component of the joined path is resolved, and symbolic links that point outside of the root are
+    /// refused.
+    Contain,
+}
+
+/// joins an `untrusted` relative path onto `root`, refusing any path that would escape `root`.
+///
+/// both `/` and `\` are treated as separators on all platforms. absolute paths, drive and unc prefixes, and `..`
+/// components that climb above `root` are refused with `ErrorKind::InvalidInput`. `..` components that stay within
+/// `root` are resolved lexically.
+///
+/// symbolic links are not inspected. see `safe_join_with`.
+///
+/// # examples.
+///
+/// ```
+/// # use ari::path::safe_join;
+/// # use std::path::PathBuf;
+///
+/// assert_eq!(safe_join("/srv/uploads", "a/./b/../c.txt").unwrap(), PathBuf::from("/srv/uploads/a/c.txt"));
+///
+/// assert!(safe_join("/srv/uploads", "../etc/passwd").is_err());
+/// assert!(safe_join("/srv/uploads", "a/../../etc/passwd").is_err());
+/// assert!(safe_join("/srv/uploads", "/etc/passwd").is_err());
+/// assert!(safe_join("/srv/uploads", r"..\..\windows").is_err());
+/// assert!(safe_join("/srv/uploads", r"C:\windows").is_err());
+/// ```
+pub fn safe_join(
+    root: impl AsRef<Path>,
+    untrusted: impl AsRef<Path>,
+) -> Result<PathBuf, std::io::Error> {
+    safe_join_with(root, untrusted, SymlinkOption::Ignore)
+}
+
+/// joins an `untrusted` relative path onto `root`, refusing any path that would escape `root`. see `safe_join`.
+///
+/// with `SymlinkOption::Contain`, symbolic links beneath `root` that resolve outside of `root` are refused with
+/// `ErrorKind::PermissionDenied`. components which do not exist yet are not checked.
+pub fn safe_join_with(
+    root: impl AsRef<Path>,
+    untrusted: impl AsRef<Path>,
+    option: SymlinkOption,
+) -> Result<PathBuf, std::io::Error> {
+    let root = root.as_ref();
+    let bytes = os_str_as_u8_slice(untrusted.as_ref().as_os_str());
+
+    if let Some(b'/') | Some(b'\\') = bytes.first() {
+        return Err(invalid_input("path is absolute"));
+    }
+
+    let mut bits = Vec::new();
+
+    for bit in bytes.split(|x| *x == b'/' || *x == b'\\') {
+        match bit {
+            b"" | b"." => {}
+            b".." => {
+                if bits.pop().is_none() {
+                    return Err(invalid_input("path escapes its root"));
+                }
+            }
+            bit if bit.contains(&b':') && cfg!(windows) => {
+                return Err(invalid_input("path contains a prefix"));
+            }
+            [letter, b':', ..] if letter.is_ascii_alphabetic() => {
+                return Err(invalid_input("path contains a prefix"));
+            }
+            bit => bits.push(bit),
+        }
+    }
+
+    let mut path = root.to_path_buf();
+
+    for bit in bits {
+        path.push(unsafe { crate::path::util::u8_slice_as_os_str(bit) });
+    }
+
+    if option == SymlinkOption::Contain {
+        ensure_contained(root, &path)?;
+    }
+
+    Ok(path)
+}
+
+// ensures that no existing component of `path` beneath `root` is a symbolic link that resolves outside of `root`.
+fn ensure_contained(root: &Path, path: &Path) -> Result<(), std::io::Error> {
+    let canonical_root = std::fs::canonicalize(root)?;
+    let relative = path
+        .strip_prefix(root)
+        .map_err(|_| invalid_input("path escapes its root"))?;
+    let mut current = root.to_path_buf();
+
+    for component in relative.components() {
+        current.push(component);
+
+        match std::fs::symlink_metadata(&current) {
+            Ok(meta) if meta.file_type().is_symlink() => match std::fs::canonicalize(&current) {
+                Ok(target) if target.starts_with(&canonical_root) => {}
+                Ok(_) => {
+                    return Err(std::io::Error::new(
+                        ErrorKind::PermissionDenied,
+                        "symbolic link points outside of its root",
+                    ))
+                }
+                Err(e) => return Err(e),
+            },
+            Ok(_) => {}
+            Err(ref e) if e.kind() == ErrorKind::NotFound => return Ok(()),
+            Err(e) => return Err(e),
+        }
+    }
+
+    Ok(())
+}
+
+fn invalid_input(message: &str) -> std::io::Error {
+    std::io::Error::new(ErrorKind::InvalidInput, message)
+}
+
+/// the platform whose file name rules `sanitize_file_name` enforces.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum TargetPlatform {
+    /// windows rules: no `<>:"/\|?*`, no reserved device names, no trailing dots or spaces, and at most 255 utf-16
+    /// code units.
+    Windows,
+
+    /// posix rules: no `/` and at most 255 bytes.
+    Posix,
+
+    /// the intersection of the windows and posix rules. names sanitized for this platform are valid everywhere.
+    Portable,
+}
+
+impl TargetPlatform {
+    /// returns the platform this program is running on.
+    pub fn host() -> TargetPlatform {
+        match cfg!(windows) {
+            true => TargetPlatform::Windows,
+            false => TargetPlatform::Posix,
+        }
+    }
+}
+
+/// the maximum length of a file name, in bytes on posix and in utf-16 code units on windows.
+const MAXIMUM_NAME_LENGTH: usize = 255;
+
+const RESERVED_NAMES: &[&str] = &[
+    "CON", "PRN", "AUX", "NUL", "COM0", "COM1", "COM2", "COM3", "COM4", "COM5", "COM6", "COM7",
+    "COM8", "COM9", "LPT0", "LPT1", "LPT2", "LPT3", "LPT4", "LPT5", "LPT6", "LPT7", "LPT8", "LPT9",
+];
+
+/// sanitizes an untrusted file name so that it is valid as a single path component on `platform`.
+///
+/// control and illegal characters are stripped, windows reserved device names are prefixed with an underscore,
+/// trailing dots and spaces are trimmed, and the name is truncated to the platform's length limits while keeping its
+/// extension where possible. names that would end up empty, `.` or `..` become `_`.
+///
+/// # examples.
+///
+/// ```
+/// # use ari::path::{sanitize_file_name, TargetPlatform};
+///
+/// assert_eq!(sanitize_file_name("report: final?.pdf", TargetPlatform::Portable), "report final.pdf");
+/// assert_eq!(sanitize_file_name("../../etc/passwd", TargetPlatform::Posix), "....etcpasswd");
+/// assert_eq!(sanitize_file_name("con.txt", TargetPlatform::Windows), "_con.txt");
+/// assert_eq!(sanitize_file_name("con.txt", TargetPlatform::Posix), "con.txt");
+/// assert_eq!(sanitize_file_name("notes. . ", TargetPlatform::Windows), "notes");
+/// assert_eq!(sanitize_file_name("\u{0}\t..", TargetPlatform::Portable), "_");
+///
+/// let long = format!("{}.tar.gz", "é".repeat(200));
+/// let sanitized = sanitize_file_name(&long, TargetPlatform::Posix);
+///
+/// assert_eq!(sanitized.len() <= 255, true);
+/// assert_eq!(sanitized.ends_with(".gz"), true);
+/// ```
+pub fn sanitize_file_name(name: &str, platform: TargetPlatform) -> String {
+    let windows = platform != TargetPlatform::Posix;
+
+    let mut name = name
+        .chars()
+        .filter(|x| !x.is_control())
+        .filter(|x| match x {
+            '/' => false,
+            '<' | '>' | ':' | '"' | '\\' | '|' | '?' | '*' => !windows,
+            _ => true,
+        })
+        .collect::<String>();
+
+    if windows {
+        trim_trailing_dots_and_spaces(&mut name);
+
+        let stem = name.split('.').next().unwrap_or("").trim_end();
+
+        if RESERVED_NAMES.iter().any(|x| x.eq_ignore_ascii_case(stem)) {
+            name.insert(0, '_');
+        }
+    }
+
+    truncate_file_name(&mut name, platform);
+
+    if windows {
+        trim_trailing_dots_and_spaces(&mut name);
+    }
+
+    match name.as_str() {
+        "" | "." | ".." => "_".to_owned(),
+        _ => name,
+    }
+}
+
+fn trim_trailing_dots_and_spaces(name: &mut String) {
+    let length = name.trim_end_matches(&['.', ' '][..]).len();
+
+    name.truncate(length);
+}
+
+// truncates `name` to the length limits of `platform`, preserving the final extension if it is reasonably short.
+fn truncate_file_name(name: &mut String, platform: TargetPlatform) {
+    let bytes = platform != TargetPlatform::Windows;
+    let units = platform != TargetPlatform::Posix;
+    let fits = |x: &str, length: usize| {
+        (!bytes || x.len() <= length) && (!units || x.encode_utf16().count() <= length)
+    };
+
+    if fits(name, MAXIMUM_NAME_LENGTH) {
+        return;
+    }
+
+    let extension = match name.rfind('.') {
+        Some(i) if i > 0 && name.len() - i <= MAXIMUM_NAME_LENGTH / 8 => name.split_off(i),
+        _ => String::new(),
+    };
+
+    let budget_bytes = MAXIMUM_NAME_LENGTH - extension.len();
+    let budget_units = MAXIMUM_NAME_LENGTH - extension.encode_utf16().count();
+    let mut used_bytes = 0;
+    let mut used_units = 0;
+    let mut end = 0;
+
+    for character in name.chars() {
+        used_bytes += character.len_utf8();
+        used_units += character.len_utf16();
+
+        if (bytes && used_bytes > budget_bytes) || (units && used_units > budget_units) {
+            break;
+        }
+
+        end = used_bytes;
+    }
+
+    name.truncate(end);
+    name.push_str(&extension);
+}
@@ -0,0 +1,74 @@
+use std::ffi::{CStr, CString, OsStr};
+use std::os::unix::ffi::OsStrExt;
+use std::os::unix::fs::{DirBuilderExt, PermissionsExt};
+use std::path::{Path, PathBuf};
+
+/// returns the home directory of the current user.
+pub(crate) fn home_directory() -> Option<PathBuf> {
+    match std::env::var_os("HOME") {
+        Some(home) if !home.is_empty() => Some(PathBuf::from(home)),
+        _ => unsafe {
+            passwd_home_directory(|passwd, buffer, result| {
+                libc::getpwuid_r(
+                    libc::getuid(),
+                    passwd,
+                    buffer.as_mut_ptr(),
+                    buffer.len(),
+                    result,
+                )
+            })
+        },
+    }
+}
+
+/// returns the home directory of `user`.
+pub(crate) fn user_home_directory(user: &str) -> Option<PathBuf> {
+    let name = CString::new(user).ok()?;
+
+    unsafe {
+        passwd_home_directory(|passwd, buffer, result| {
+            libc::getpwnam_r(
+                name.as_ptr(),
+                passwd,
+                buffer.as_mut_ptr(),
+                buffer.len(),
+                result,
+            )
+        })
+    }
+}
+
+// calls a `getpw*_r` style `function`, returning the home directory from the resulting passwd entry.
+unsafe fn passwd_home_directory<TFunction>(function: TFunction) -> Option<PathBuf>
+where
+    TFunction: Fn(*mut libc::passwd, &mut [libc::c_char], *mut *mut libc::passwd) -> libc::c_int,
+{
+    let mut buffer = vec![0 as libc::c_char; 16 * 1024];
+    let mut passwd = std::mem::zeroed::<libc::passwd>();
+    let mut result = std::ptr::null_mut();
+
+    match function(&mut passwd, &mut buffer, &mut result) {
+        0 if !result.is_null() && !passwd.pw_dir.is_null() => {
+            let directory = CStr::from_ptr(passwd.pw_dir);
+
+            Some(PathBuf::from(OsStr::from_bytes(directory.to_bytes())))
+        }
+        _ => None,
+    }
+}
+
+/// creates `path` and any missing parents, making each directory created only accessible by the current user.
+pub(crate) fn create_private_directory(path: &Path) -> Result<(), std::io::Error> {
+    std::fs::DirBuilder::new()
+        .recursive(true)
+        .mode(0o700)
+        .create(path)
+}
+
+/// returns true if `path` is a file with any execute permission bit set.
+pub(crate) fn is_executable(path: &Path) -> bool {
+    match std::fs::metadata(path) {
+        Ok(meta) => meta.is_file() && meta.permissions().mode() & 0o111 != 0,
+        Err(_) => false,
+    }
+}
@@ -0,0 +1,36 @@
+use std::path::{Path, PathBuf};
+
+/// returns the home directory of the current user.
+pub(crate) fn home_directory() -> Option<PathBuf> {
+    if let Some(profile) = std::env::var_os("USERPROFILE").filter(|x| !x.is_empty()) {
+        return Some(PathBuf::from(profile));
+    }
+
+    match (std::env::var_os("HOMEDRIVE"), std::env::var_os("HOMEPATH")) {
+        (Some(drive), Some(path)) => {
+            let mut home = drive;
+
+            home.push(path);
+            Some(PathBuf::from(home))
+        }
+        _ => None,
+    }
+}
+
+/// returns the home directory of `user`. windows offers no simple way to look up another user's profile, so this
+/// always returns `None`.
+pub(crate) fn user_home_directory(_user: &str) -> Option<PathBuf> {
+    None
+}
+
+/// creates `path` and any missing parents. directories beneath the user's profile inherit its access control list, so
+/// no further restriction is applied.
+pub(crate) fn create_private_directory(path: &Path) -> Result<(), std::io::Error> {
+    std::fs::create_dir_all(path)
+}
+
+/// returns true if `path` is a file. whether a file is executable on windows is decided by its extension, which the
+/// caller is responsible for.
+pub(crate) fn is_executable(path: &Path) -> bool {
+    crate::fs::file_exists(path)
+}
@@ -16,10 +16,10 @@ pub fn _ari_path_append(destination: &mut PathBuf, path: impl AsRef<Path>) {
 }
 
 // why this is ok: https://github.com/rust-lang/rust/blob/b16c7a235fa0f57fed6b7ec13ffd3cff1bcdd9ad/src/libstd/path.rs#L88
-fn os_str_as_u8_slice(os: &OsStr) -> &[u8] {
+pub(crate) fn os_str_as_u8_slice(os: &OsStr) -> &[u8] {
     unsafe { &*(os as *const OsStr as *const [u8]) }
 }
 
-unsafe fn u8_slice_as_os_str(slice: &[u8]) -> &OsStr {
+pub(crate) unsafe fn u8_slice_as_os_str(slice: &[u8]) -> &OsStr {
     &*(slice as *const [u8] as *const OsStr)
 }
@@ -0,0 +1,159 @@
+use std::ffi::{OsStr, OsString};
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+
+/// the extensions searched on windows when `PATHEXT` is not set.
+const DEFAULT_PATHEXT: &str = ".COM;.EXE;.BAT;.CMD";
+
+/// returns the first executable named `name` on `PATH`.
+///
+/// if `name` contains a separator, it is resolved against the current directory instead of being searched for. on
+/// windows, the extensions listed in `PATHEXT` are tried when `name` lacks one. on unix, only files with an execute
+/// permission bit set are considered.
+///
+/// fails with `ErrorKind::NotFound` if no executable is found.
+pub fn which(name: impl AsRef<OsStr>) -> Result<PathBuf, std::io::Error> {
+    let directory = std::env::current_dir()?;
+
+    which_in(name, None, directory)
+}
+
+/// returns every executable named `name` on `PATH`, in order of precedence. the first entry is the one that `which`
+/// would return. see `which`.
+pub fn which_all(name: impl AsRef<OsStr>) -> Result<Vec<PathBuf>, std::io::Error> {
+    let directory = std::env::current_dir()?;
+
+    Ok(which_all_in(name, None, directory))
+}
+
+/// returns the first executable named `name` within `paths`, a `PATH` style list of directories, or within `PATH` if
+/// `paths` is `None`. relative directories, and `name` itself if it contains a separator, are resolved against
+/// `directory`. see `which`.
+///
+/// # examples.
+///
+/// ```
+/// # use ari::path;
+/// # use std::ffi::OsStr;
+/// # use std::path::Path;
+///
+/// let directory = std::env::temp_dir().join("ari-which-example");
+/// let tool = directory.join(if cfg!(windows) { "tool.EXE" } else { "tool" });
+///
+/// std::fs::create_dir_all(&directory).unwrap();
+/// std::fs::write(&tool, b"").unwrap();
+///
+/// #[cfg(unix)]
+/// {
+///     use std::os::unix::fs::PermissionsExt;
+///
+///     std::fs::set_permissions(&tool, std::fs::Permissions::from_mode(0o755)).unwrap();
+/// }
+///
+/// let paths = std::env::join_paths([Path::new("/nonexistent"), &directory]).unwrap();
+///
+/// assert_eq!(path::which_in("tool", Some(paths.as_os_str()), "/").unwrap(), tool);
+/// assert_eq!(path::which_all_in("tool", Some(paths.as_os_str()), "/"), vec![tool]);
+///
+/// let error = path::which_in("ari-which-missing", Some(OsStr::new("/nonexistent")), "/").unwrap_err();
+///
+/// assert_eq!(error.kind(), std::io::ErrorKind::NotFound);
+/// assert_eq!(error.to_string(), "`ari-which-missing` was not found on the search path");
+/// ```
+pub fn which_in(
+    name: impl AsRef<OsStr>,
+    paths: Option<&OsStr>,
+    directory: impl AsRef<Path>,
+) -> Result<PathBuf, std::io::Error> {
+    let name = name.as_ref();
+
+    candidates(name, paths, directory.as_ref())
+        .into_iter()
+        .find(|x| crate::path::sys::is_executable(x))
+        .ok_or_else(|| {
+            let message = format!(
+                "`{}` was not found on the search path",
+                name.to_string_lossy()
+            );
+
+            std::io::Error::new(ErrorKind::NotFound, message)
+        })
+}
+
+/// returns every executable named `name` within `paths`, or within `PATH` if `paths` is `None`, in order of precedence.
+/// see `which_in`.
+pub fn which_all_in(
+    name: impl AsRef<OsStr>,
+    paths: Option<&OsStr>,
+    directory: impl AsRef<Path>,
+) -> Vec<PathBuf> {
+    let mut found = Vec::<PathBuf>::new();
+
+    for candidate in candidates(name.as_ref(), paths, directory.as_ref()) {
+        if crate::path::sys::is_executable(&candidate) && !found.contains(&candidate) {
+            found.push(candidate);
+        }
+    }
+
+    found
+}
+
+// returns every path that could satisfy a lookup of `name`, in order of precedence.
+fn candidates(name: &OsStr, paths: Option<&OsStr>, directory: &Path) -> Vec<PathBuf> {
+    let extensions = extensions(name);
+    let expand = |base: PathBuf| {
+        extensions.iter().map(move |extension| {
+            let mut path = base.clone().into_os_string();
+
+            path.push(extension);
+            PathBuf::from(path)
+        })
+    };
+
+    if Path::new(name).components().count() > 1 || Path::new(name).is_absolute() {
+        return expand(directory.join(name)).collect();
+    }
+
+    let paths = match paths {
+        Some(x) => std::env::split_paths(x).collect::<Vec<_>>(),
+        None => match std::env::var_os("PATH") {
+            Some(x) => std::env::split_paths(&x).collect::<Vec<_>>(),
+            None => return vec![],
+        },
+    };
+
+    paths
+        .into_iter()
+        .flat_map(|x| expand(directory.join(x).join(name)))
+        .collect()
+}
+
+// returns the extensions to try appending to `name`. the empty extension means `name` as given. on windows, a name
+// without an executable extension is never run as-is.
+fn extensions(name: &OsStr) -> Vec<OsString> {
+    if !cfg!(windows) {
+        return vec![OsString::new()];
+    }
+
+    let value = std::env::var("PATHEXT").unwrap_or_else(|_| DEFAULT_PATHEXT.to_owned());
+    let extensions = value
+        .split(';')
+        .filter(|x| !x.is_empty())
+        .map(OsString::from)
+        .collect::<Vec<_>>();
+    let executable = match Path::new(name).extension() {
+        Some(existing) => {
+            let existing = format!(".{}", existing.to_string_lossy());
+
+            extensions
+                .iter()
+                .any(|x| x.to_string_lossy().eq_ignore_ascii_case(&existing))
+        }
+        None => false,
+    };
+
+    match executable {
+        true => vec![OsString::new()],
+        false => extensions,
+    }
+}
@@ -0,0 +1,559 @@
+use std::fmt::{Debug, Display, Formatter};
+use std::hash::{Hash, Hasher};
+
+use crate::path::PosixPath;
+
+/// a windows path prefix.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum WindowsPrefix<'a> {
+    /// a verbatim prefix, `\\?\name`.
+    Verbatim(&'a str),
+
+    /// a verbatim unc prefix, `\\?\UNC\server\share`.
+    VerbatimUnc(&'a str, &'a str),
+
+    /// a verbatim disk prefix, `\\?\C:`. the drive letter is always uppercase.
+    VerbatimDisk(u8),
+
+    /// a device namespace prefix, `\\.\name`.
+    DeviceNs(&'a str),
+
+    /// a unc prefix, `\\server\share`.
+    Unc(&'a str, &'a str),
+
+    /// a disk prefix, `C:`. the drive letter is always uppercase.
+    Disk(u8),
+}
+
+impl WindowsPrefix<'_> {
+    /// returns true if this prefix is verbatim, meaning that `/`, `.` and `..` have no special meaning after it.
+    pub fn is_verbatim(&self) -> bool {
+        matches!(
+            self,
+            WindowsPrefix::Verbatim(_)
+                | WindowsPrefix::VerbatimUnc(..)
+                | WindowsPrefix::VerbatimDisk(_)
+        )
+    }
+
+    // returns true if paths with this prefix are rooted, even without a trailing separator.
+    fn has_implicit_root(&self) -> bool {
+        !matches!(self, WindowsPrefix::Disk(_))
+    }
+}
+
+impl Display for WindowsPrefix<'_> {
+    fn fmt(&self, formatter: &mut Formatter) -> Result<(), std::fmt::Error> {
+        match *self {
+            WindowsPrefix::Verbatim(name) => write!(formatter, r"\\?\{}", name),
+            WindowsPrefix::VerbatimUnc(server, share) => {
+                write!(formatter, r"\\?\UNC\{}\{}", server, share)
+            }
+            WindowsPrefix::VerbatimDisk(disk) => write!(formatter, r"\\?\{}:", disk as char),
+            WindowsPrefix::DeviceNs(name) => write!(formatter, r"\\.\{}", name),
+            WindowsPrefix::Unc(server, share) => write!(formatter, r"\\{}\{}", server, share),
+            WindowsPrefix::Disk(disk) => write!(formatter, "{}:", disk as char),
+        }
+    }
+}
+
+/// a single component of a `WindowsPath`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum WindowsComponent<'a> {
+    Prefix(WindowsPrefix<'a>),
+    RootDir,
+    CurDir,
+    ParentDir,
+    Normal(&'a str),
+}
+
+/// a windows path, parsed and joined by windows rules regardless of the host platform.
+///
+/// both `\` and `/` are treated as separators, except after a verbatim (`\\?\`) prefix. comparisons are
+/// case-insensitive and ignore redundant separators and `.` components.
+///
+/// # examples.
+///
+/// ```
+/// # use ari::path::{WindowsComponent, WindowsPath, WindowsPrefix};
+///
+/// let path = WindowsPath::new(r"C:\Program Files").join("ari/ari.exe");
+///
+/// assert_eq!(path.as_str(), r"C:\Program Files\ari/ari.exe");
+/// assert_eq!(path, WindowsPath::new(r"c:\program files\ARI\ARI.EXE"));
+/// assert_eq!(path.prefix(), Some(WindowsPrefix::Disk(b'C')));
+/// assert_eq!(path.file_name(), Some("ari.exe"));
+/// assert_eq!(path.is_absolute(), true);
+///
+/// let share = WindowsPath::new(r"\\server\share\dir");
+///
+/// assert_eq!(share.components().next(), Some(WindowsComponent::Prefix(WindowsPrefix::Unc("server", "share"))));
+/// assert_eq!(share.join(r"\other").as_str(), r"\\server\share\other");
+/// assert_eq!(share.join(r"D:\x").as_str(), r"D:\x");
+/// ```
+#[derive(Clone, Default)]
+pub struct WindowsPath {
+    inner: String,
+}
+
+impl WindowsPath {
+    pub fn new(path: impl Into<String>) -> WindowsPath {
+        WindowsPath { inner: path.into() }
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.inner
+    }
+
+    pub fn into_string(self) -> String {
+        self.inner
+    }
+
+    /// returns the prefix of this path, if any.
+    pub fn prefix(&self) -> Option<WindowsPrefix<'_>> {
+        Parsed::new(&self.inner).prefix
+    }
+
+    /// returns true if this path has a root, either explicitly with a leading separator or implicitly through a
+    /// non-disk prefix.
+    pub fn has_root(&self) -> bool {
+        let parsed = Parsed::new(&self.inner);
+
+        parsed.root
+            || parsed
+                .prefix
+                .map(|x| x.has_implicit_root())
+                .unwrap_or(false)
+    }
+
+    /// returns true if this path is absolute. `\foo` and `C:foo` are not absolute, `C:\foo` and `\\server\share` are.
+    pub fn is_absolute(&self) -> bool {
+        Parsed::new(&self.inner).is_absolute()
+    }
+
+    pub fn is_relative(&self) -> bool {
+        !self.is_absolute()
+    }
+
+    /// returns an iterator over the components of this path.
+    pub fn components(&self) -> impl Iterator<Item = WindowsComponent<'_>> {
+        let parsed = Parsed::new(&self.inner);
+        let prefix = parsed.prefix.map(WindowsComponent::Prefix);
+        let root = match parsed.root {
+            true => Some(WindowsComponent::RootDir),
+            false => None,
+        };
+
+        prefix
+            .into_iter()
+            .chain(root)
+            .chain(parsed.body().map(|(_, x)| x))
+    }
+
+    /// returns the final component of this path, if it is a normal file or directory name.
+    pub fn file_name(&self) -> Option<&str> {
+        match Parsed::new(&self.inner).body().last() {
+            Some((_, WindowsComponent::Normal(x))) => Some(x),
+            _ => None,
+        }
+    }
+
+    /// returns this path without its final component, or `None` if it has no final component.
+    pub fn parent(&self) -> Option<WindowsPath> {
+        let parsed = Parsed::new(&self.inner);
+        let (offset, _) = parsed.body().last()?;
+        let minimum = parsed.body_offset();
+        let bytes = self.inner.as_bytes();
+        let mut end = offset;
+
+        while end > minimum && parsed.is_separator(bytes[end - 1]) {
+            end -= 1;
+        }
+
+        Some(WindowsPath::new(&self.inner[..end]))
+    }
+
+    /// extends this path with `path`, following the rules of `std::path::PathBuf::push` on windows:
+    ///
+    /// - if `path` is absolute, or has a prefix, it replaces this path.
+    /// - if `path` has a root but no prefix, it replaces everything except this path's prefix.
+    /// - otherwise, `path` is appended with a separator.
+    pub fn push(&mut self, path: impl AsRef<str>) {
+        let path = path.as_ref();
+        let other = Parsed::new(path);
+
+        if other.is_absolute() || other.prefix.is_some() {
+            self.inner.clear();
+            self.inner.push_str(path);
+            return;
+        }
+
+        let parsed = Parsed::new(&self.inner);
+        let verbatim = parsed.prefix.map(|x| x.is_verbatim()).unwrap_or(false);
+
+        if other.root {
+            let length = parsed.prefix_length;
+
+            self.inner.truncate(length);
+        } else if parsed.needs_separator() {
+            self.inner.push('\\');
+        }
+
+        match verbatim {
+            true => self.inner.push_str(&path.replace('/', "\\")),
+            false => self.inner.push_str(path),
+        }
+    }
+
+    /// returns a new path with `path` pushed onto it. see `push`.
+    pub fn join(&self, path: impl AsRef<str>) -> WindowsPath {
+        let mut joined = self.clone();
+
+        joined.push(path);
+        joined
+    }
+
+    /// appends a `path` to self. always treats `path` as a relative path, like `PathBufExt::append`.
+    ///
+    /// # examples.
+    ///
+    /// ```
+    /// # use ari::path::WindowsPath;
+    ///
+    /// let mut path = WindowsPath::new(r"C:\var");
+    /// path.append(r"\bin/D:\ari.so");
+    ///
+    /// assert_eq!(path.as_str(), r"C:\var\bin\D:\ari.so");
+    /// ```
+    pub fn append(&mut self, path: impl AsRef<str>) {
+        for bit in path.as_ref().split(&['/', '\\'][..]) {
+            if !bit.is_empty() {
+                if Parsed::new(&self.inner).needs_separator() {
+                    self.inner.push('\\');
+                }
+
+                self.inner.push_str(bit);
+            }
+        }
+    }
+
+    /// returns the volume name for this path, intended for display. see `ari::path::volume_name`.
+    pub fn volume_name(&self) -> Option<String> {
+        let volume = match self.components().next()? {
+            WindowsComponent::Prefix(prefix) => match prefix {
+                WindowsPrefix::Verbatim(name) => name.to_owned(),
+                WindowsPrefix::VerbatimUnc(server, share) => format!("{}\\{}", server, share),
+                WindowsPrefix::VerbatimDisk(disk) => format!("{}:\\", disk as char),
+                WindowsPrefix::DeviceNs(namespace) => namespace.to_owned(),
+                WindowsPrefix::Unc(server, share) => format!("{}\\{}\\", server, share),
+                WindowsPrefix::Disk(disk) => format!("{}:\\", disk as char),
+            },
+            WindowsComponent::RootDir => "\\".to_owned(),
+            WindowsComponent::CurDir => ".".to_owned(),
+            WindowsComponent::ParentDir => "..".to_owned(),
+            WindowsComponent::Normal(x) => x.to_owned(),
+        };
+
+        Some(volume)
+    }
+
+    /// converts this path into a posix path, msys style.
+    ///
+    /// disk prefixes become a leading single letter directory (`C:\x` => `/c/x`), unc prefixes become a leading double
+    /// slash (`\\server\share\x` => `//server/share/x`), and `\\?\` and `\\.\` prefixes are kept as `//?/` and `//./`.
+    ///
+    /// # examples.
+    ///
+    /// ```
+    /// # use ari::path::WindowsPath;
+    ///
+    /// assert_eq!(WindowsPath::new(r"C:\var\bin").to_posix().as_str(), "/c/var/bin");
+    /// assert_eq!(WindowsPath::new(r"\\server\share\bin").to_posix().as_str(), "//server/share/bin");
+    /// assert_eq!(WindowsPath::new(r"ari\..\hello.so").to_posix().as_str(), "ari/../hello.so");
+    /// ```
+    pub fn to_posix(&self) -> PosixPath {
+        let mut path = String::with_capacity(self.inner.len() + 1);
+
+        for component in self.components() {
+            match component {
+                WindowsComponent::Prefix(prefix) => match prefix {
+                    WindowsPrefix::Disk(disk) | WindowsPrefix::VerbatimDisk(disk) => {
+                        path.push('/');
+                        path.push(disk.to_ascii_lowercase() as char);
+                    }
+                    WindowsPrefix::Unc(server, share)
+                    | WindowsPrefix::VerbatimUnc(server, share) => {
+                        path.push_str(&format!("//{}/{}", server, share));
+                    }
+                    WindowsPrefix::Verbatim(name) => path.push_str(&format!("//?/{}", name)),
+                    WindowsPrefix::DeviceNs(name) => path.push_str(&format!("//./{}", name)),
+                },
+                WindowsComponent::RootDir if path.is_empty() => path.push('/'),
+                WindowsComponent::RootDir => {}
+                component => {
+                    if !path.is_empty() && !path.ends_with('/') {
+                        path.push('/');
+                    }
+
+                    path.push_str(match component {
+                        WindowsComponent::CurDir => ".",
+                        WindowsComponent::ParentDir => "..",
+                        WindowsComponent::Normal(x) => x,
+                        _ => unreachable!(),
+                    });
+                }
+            }
+        }
+
+        PosixPath::new(path)
+    }
+}
+
+impl Debug for WindowsPath {
+    fn fmt(&self, formatter: &mut Formatter) -> Result<(), std::fmt::Error> {
+        Debug::fmt(&self.inner, formatter)
+    }
+}
+
+impl Display for WindowsPath {
+    fn fmt(&self, formatter: &mut Formatter) -> Result<(), std::fmt::Error> {
+        Display::fmt(&self.inner, formatter)
+    }
+}
+
+impl PartialEq for WindowsPath {
+    fn eq(&self, other: &WindowsPath) -> bool {
+        let mut a = self.components();
+        let mut b = other.components();
+
+        loop {
+            match (a.next(), b.next()) {
+                (None, None) => return true,
+                (Some(x), Some(y)) if component_eq(x, y) => continue,
+                _ => return false,
+            }
+        }
+    }
+}
+
+impl Eq for WindowsPath {}
+
+impl Hash for WindowsPath {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        fn hash_str<H: Hasher>(string: &str, state: &mut H) {
+            string
+                .chars()
+                .flat_map(char::to_uppercase)
+                .for_each(|x| x.hash(state));
+            state.write_u8(0xff);
+        }
+
+        for component in self.components() {
+            match component {
+                WindowsComponent::Prefix(prefix) => hash_str(&prefix.to_string(), state),
+                WindowsComponent::RootDir => state.write_u8(1),
+                WindowsComponent::CurDir => state.write_u8(2),
+                WindowsComponent::ParentDir => state.write_u8(3),
+                WindowsComponent::Normal(x) => hash_str(x, state),
+            }
+        }
+    }
+}
+
+impl AsRef<str> for WindowsPath {
+    fn as_ref(&self) -> &str {
+        &self.inner
+    }
+}
+
+impl From<&str> for WindowsPath {
+    fn from(path: &str) -> WindowsPath {
+        WindowsPath::new(path)
+    }
+}
+
+impl From<String> for WindowsPath {
+    fn from(path: String) -> WindowsPath {
+        WindowsPath::new(path)
+    }
+}
+
+// compares two path components the way windows does: case-insensitively.
+fn component_eq(a: WindowsComponent, b: WindowsComponent) -> bool {
+    fn eq(a: &str, b: &str) -> bool {
+        a.chars()
+            .flat_map(char::to_uppercase)
+            .eq(b.chars().flat_map(char::to_uppercase))
+    }
+
+    match (a, b) {
+        (WindowsComponent::Prefix(x), WindowsComponent::Prefix(y)) => {
+            eq(&x.to_string(), &y.to_string())
+        }
+        (WindowsComponent::Normal(x), WindowsComponent::Normal(y)) => eq(x, y),
+        (x, y) => x == y,
+    }
+}
+
+// a path split into its prefix, root and body.
+struct Parsed<'a> {
+    path: &'a str,
+    prefix: Option<WindowsPrefix<'a>>,
+    prefix_length: usize,
+    root: bool,
+}
+
+impl<'a> Parsed<'a> {
+    fn new(path: &'a str) -> Parsed<'a> {
+        let (prefix, prefix_length) = match parse_prefix(path) {
+            Some((prefix, length)) => (Some(prefix), length),
+            None => (None, 0),
+        };
+
+        let mut parsed = Parsed {
+            path,
+            prefix,
+            prefix_length,
+            root: false,
+        };
+
+        parsed.root = path
+            .as_bytes()
+            .get(prefix_length)
+            .map(|x| parsed.is_separator(*x))
+            .unwrap_or(false);
+
+        parsed
+    }
+
+    fn verbatim(&self) -> bool {
+        self.prefix.map(|x| x.is_verbatim()).unwrap_or(false)
+    }
+
+    fn is_separator(&self, byte: u8) -> bool {
+        byte == b'\\' || (byte == b'/' && !self.verbatim())
+    }
+
+    fn is_absolute(&self) -> bool {
+        match self.prefix {
+            Some(WindowsPrefix::Disk(_)) => self.root,
+            Some(_) => true,
+            None => false,
+        }
+    }
+
+    fn body_offset(&self) -> usize {
+        self.prefix_length + self.root as usize
+    }
+
+    // returns true if a separator must be inserted before appending a relative component.
+    fn needs_separator(&self) -> bool {
+        let last = match self.path.as_bytes().last() {
+            Some(x) => *x,
+            None => return false,
+        };
+
+        let bare_disk = match self.prefix {
+            Some(WindowsPrefix::Disk(_)) => self.prefix_length == self.path.len(),
+            _ => false,
+        };
+
+        !self.is_separator(last) && !bare_disk
+    }
+
+    // returns the body components of this path, and the byte offset at which each of them begins.
+    fn body(&self) -> impl Iterator<Item = (usize, WindowsComponent<'a>)> {
+        let verbatim = self.verbatim();
+        let leading = self.prefix.is_none() && !self.root;
+        let start = self.body_offset();
+        let body = &self.path[start..];
+        let mut offset = start;
+
+        body.split(move |x| x == '\\' || (x == '/' && !verbatim))
+            .enumerate()
+            .filter_map(move |(i, x)| {
+                let position = offset;
+
+                offset += x.len() + 1;
+
+                let component = match x {
+                    "" => return None,
+                    "." if verbatim || (leading && i == 0) => WindowsComponent::CurDir,
+                    "." => return None,
+                    ".." => WindowsComponent::ParentDir,
+                    x => WindowsComponent::Normal(x),
+                };
+
+                Some((position, component))
+            })
+    }
+}
+
+// parses the prefix of a windows path, returning it and its length in bytes.
+fn parse_prefix(path: &str) -> Option<(WindowsPrefix<'_>, usize)> {
+    fn separator(x: u8) -> bool {
+        x == b'\\' || x == b'/'
+    }
+
+    // returns the text up until the next separator.
+    fn next(path: &str, verbatim: bool) -> &str {
+        let end = path
+            .bytes()
+            .position(|x| x == b'\\' || (x == b'/' && !verbatim))
+            .unwrap_or(path.len());
+
+        &path[..end]
+    }
+
+    fn drive(path: &str) -> Option<u8> {
+        match path.as_bytes() {
+            [letter, b':', ..] if letter.is_ascii_alphabetic() => Some(letter.to_ascii_uppercase()),
+            _ => None,
+        }
+    }
+
+    let bytes = path.as_bytes();
+
+    if let Some(rest) = path.strip_prefix(r"\\?\") {
+        if let Some(rest) = rest.strip_prefix(r"UNC\") {
+            let server = next(rest, true);
+            let share = next(rest.get(server.len() + 1..).unwrap_or(""), true);
+            let length = 8 + server.len() + if share.is_empty() { 0 } else { share.len() + 1 };
+
+            return Some((
+                WindowsPrefix::VerbatimUnc(server, share),
+                length.min(path.len()),
+            ));
+        }
+
+        let name = next(rest, true);
+
+        return match drive(name) {
+            Some(disk) if name.len() == 2 => Some((WindowsPrefix::VerbatimDisk(disk), 6)),
+            _ => Some((WindowsPrefix::Verbatim(name), 4 + name.len())),
+        };
+    }
+
+    if bytes.len() >= 2 && separator(bytes[0]) && separator(bytes[1]) {
+        let rest = &path[2..];
+
+        if rest.len() >= 2 && rest.as_bytes()[0] == b'.' && separator(rest.as_bytes()[1]) {
+            let name = next(&rest[2..], false);
+
+            return Some((WindowsPrefix::DeviceNs(name), 4 + name.len()));
+        }
+
+        let server = next(rest, false);
+        let share = next(rest.get(server.len() + 1..).unwrap_or(""), false);
+
+        return match !server.is_empty() && !share.is_empty() {
+            true => Some((
+                WindowsPrefix::Unc(server, share),
+                3 + server.len() + share.len(),
+            )),
+            false => None,
+        };
+    }
+
+    drive(path).map(|x| (WindowsPrefix::Disk(x), 2))
+}
@@ -0,0 +1,214 @@
+use std::borrow::Cow;
+use unicode_width::UnicodeWidthChar;
+
+use crate::str::TextAlignment;
+
+/// removes ansi escape sequences, such as colors and hyperlinks, from `text`.
+///
+/// csi sequences, string sequences such as osc, dcs and apc, and two-character escapes are all removed.
+///
+/// # examples.
+///
+/// ```
+/// let text = "\x1b[1;32mok\x1b[0m \x1b]8;;https://example.com\x1b\\link\x1b]8;;\x1b\\";
+///
+/// assert_eq!(ari::str::strip_ansi(text), "ok link");
+/// assert_eq!(ari::str::strip_ansi("plain"), "plain");
+/// ```
+pub fn strip_ansi(text: &str) -> Cow<'_, str> {
+    if !has_escapes(text) {
+        return Cow::Borrowed(text);
+    }
+
+    Cow::Owned(
+        segments(text)
+            .filter_map(|x| match x {
+                Segment::Text(x) => Some(x),
+                Segment::Escape(_) => None,
+            })
+            .collect(),
+    )
+}
+
+/// returns the number of terminal columns `text` occupies, ignoring ansi escape sequences.
+///
+/// # examples.
+///
+/// ```
+/// assert_eq!(ari::str::display_width("\x1b[31m漢字\x1b[0m!"), 5);
+/// ```
+pub fn display_width(text: &str) -> usize {
+    segments(text)
+        .map(|x| match x {
+            Segment::Text(x) => text_width(x),
+            Segment::Escape(_) => 0,
+        })
+        .sum()
+}
+
+/// pads `text` to `width` columns with `character`, measuring only its visible text. escape sequences are kept
+/// intact. see `PadString::pad`.
+///
+/// # examples.
+///
+/// ```
+/// # use ari::str::TextAlignment;
+///
+/// let padded = ari::str::pad_ansi("\x1b[31mred\x1b[0m", 5, '.', TextAlignment::Right);
+///
+/// assert_eq!(padded, "..\x1b[31mred\x1b[0m");
+/// ```
+pub fn pad_ansi(text: &str, width: usize, character: char, alignment: TextAlignment) -> String {
+    let required = width.saturating_sub(display_width(text));
+    let mut string = String::with_capacity(text.len() + required);
+    let (left, right) = match alignment {
+        TextAlignment::Left => (0, required),
+        TextAlignment::Right => (required, 0),
+    };
+
+    (0..left).for_each(|_| string.push(character));
+    string.push_str(text);
+    (0..right).for_each(|_| string.push(character));
+
+    string
+}
+
+/// shortens `text` to at most `width` visible columns, ending it with `ellipsis` if anything was removed.
+///
+/// every escape sequence is kept, including those after the point of truncation, so that styles are still reset.
+///
+/// # examples.
+///
+/// ```
+/// let truncated = ari::str::truncate_ansi("\x1b[1mbold text\x1b[0m", 6, "…");
+///
+/// assert_eq!(truncated, "\x1b[1mbold …\x1b[0m");
+/// assert_eq!(ari::str::truncate_ansi("short", 6, "…"), "short");
+/// ```
+pub fn truncate_ansi(text: &str, width: usize, ellipsis: &str) -> String {
+    if display_width(text) <= width {
+        return text.to_owned();
+    }
+
+    let ellipsis = match display_width(ellipsis) <= width {
+        true => ellipsis,
+        false => "",
+    };
+
+    let available = width - display_width(ellipsis);
+    let mut truncated = String::with_capacity(text.len());
+    let mut used = 0;
+    let mut ended = false;
+
+    for segment in segments(text) {
+        match segment {
+            Segment::Escape(x) => truncated.push_str(x),
+            Segment::Text(_) if ended => {}
+            Segment::Text(x) => {
+                for character in x.chars() {
+                    let columns = character.width().unwrap_or(0);
+
+                    if used + columns > available {
+                        truncated.push_str(ellipsis);
+                        ended = true;
+                        break;
+                    }
+
+                    truncated.push(character);
+                    used += columns;
+                }
+            }
+        }
+    }
+
+    truncated
+}
+
+fn text_width(text: &str) -> usize {
+    text.chars().map(|x| x.width().unwrap_or(0)).sum()
+}
+
+fn has_escapes(text: &str) -> bool {
+    text.contains(['\x1b', '\u{9b}'])
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Segment<'a> {
+    Text(&'a str),
+    Escape(&'a str),
+}
+
+// splits `text` into runs of visible text and individual escape sequences. an unterminated escape sequence extends to
+// the end of `text`.
+fn segments(text: &str) -> impl Iterator<Item = Segment<'_>> {
+    let mut rest = text;
+
+    std::iter::from_fn(move || {
+        if rest.is_empty() {
+            return None;
+        }
+
+        let start = rest.find(['\x1b', '\u{9b}']).unwrap_or(rest.len());
+
+        let (segment, length) = match start {
+            0 => {
+                let length = escape_length(rest);
+
+                (Segment::Escape(&rest[..length]), length)
+            }
+            start => (Segment::Text(&rest[..start]), start),
+        };
+
+        rest = &rest[length..];
+
+        Some(segment)
+    })
+}
+
+// returns the length in bytes of the escape sequence at the start of `text`.
+fn escape_length(text: &str) -> usize {
+    let bytes = text.as_bytes();
+
+    // the c1 control `\u{9b}` is a single character csi, encoded in two bytes.
+    let (csi, start) = match bytes {
+        [0xc2, 0x9b, ..] => (true, 2),
+        [0x1b, b'[', ..] => (true, 2),
+        _ => (false, 1),
+    };
+
+    if csi {
+        // parameter and intermediate bytes, followed by a single final byte.
+        return match bytes[start..]
+            .iter()
+            .position(|x| (0x40..=0x7e).contains(x))
+        {
+            Some(end) => start + end + 1,
+            None => text.len(),
+        };
+    }
+
+    match bytes.get(1) {
+        // string sequences: osc, dcs, sos, pm and apc, terminated by st (`esc \`) or, for osc, bel.
+        Some(b']' | b'P' | b'X' | b'^' | b'_') => {
+            let mut i = 2;
+
+            while i < bytes.len() {
+                match bytes[i] {
+                    0x07 => return i + 1,
+                    0x1b if bytes.get(i + 1) == Some(&b'\\') => return i + 2,
+                    _ => i += 1,
+                }
+            }
+
+            text.len()
+        }
+
+        // two-character escapes, optionally with intermediate bytes, such as `esc 7` and `esc ( b`.
+        Some(_) => match bytes[1..].iter().position(|x| !(0x20..=0x2f).contains(x)) {
+            Some(end) if (0x30..=0x7e).contains(&bytes[1 + end]) => end + 2,
+            _ => 1,
+        },
+
+        None => 1,
+    }
+}
@@ -1,3 +1,7 @@
+mod ansi;
+
+pub use self::ansi::*;
+
 use std::string::FromUtf16Error;
 use unicode_width::UnicodeWidthStr;
 